@@ -5,7 +5,9 @@ mod state;
 use std::collections::HashSet;
 
 use self::state::Activity;
-use activity::{ActivityError, AnnounceParams, CreateParams, Message, Operation, VoteType};
+use activity::{
+    ActivityError, AnnounceParams, CreateParams, Message, ObjectType, Operation, VoteType,
+};
 use async_trait::async_trait;
 use feed::FeedAbi;
 use foundation::FoundationAbi;
@@ -104,8 +106,12 @@ impl Contract for Activity {
     ) -> Result<ExecutionResult<Self::Message>, Self::Error> {
         match message {
             Message::Create { params } => {
-                self._create_activity(context.authenticated_signer.unwrap(), params.clone())
-                    .await?;
+                self._create_activity(
+                    context.authenticated_signer.unwrap(),
+                    params.clone(),
+                    context.chain_id == system_api::current_application_id().creation.chain_id,
+                )
+                .await?;
                 let dest =
                     Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
                 Ok(ExecutionResult::default()
@@ -122,6 +128,10 @@ impl Contract for Activity {
                 activity_id,
                 object_id,
             } => {
+                if context.chain_id == system_api::current_application_id().creation.chain_id {
+                    self.check_object_classes(activity_id, object_id.clone())
+                        .await?;
+                }
                 self.register(activity_id, object_id.clone()).await?;
                 let dest =
                     Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
@@ -251,6 +261,9 @@ impl Activity {
             cid: params.cid,
             title: params.title,
             content: params.content,
+            tags: Vec::new(),
+            authors: Vec::new(),
+            publish_at: None,
         };
         self.call_application(true, Self::review_app_id()?, &call, vec![])
             .await?;
@@ -269,8 +282,12 @@ impl Activity {
         &mut self,
         owner: Owner,
         params: CreateParams,
+        creation_chain: bool,
     ) -> Result<(), ActivityError> {
         let activity_id = self.create_activity(owner, params.clone()).await?;
+        if creation_chain {
+            self.open_activity_funds(activity_id).await?;
+        }
         let call = review::ApplicationCall::SubmitActivity {
             activity_id,
             activity_host: owner,
@@ -289,6 +306,29 @@ impl Activity {
         Ok(approved)
     }
 
+    async fn check_object_classes(
+        &mut self,
+        activity_id: u64,
+        object_id: String,
+    ) -> Result<(), ActivityError> {
+        let activity = self.activity(activity_id).await?;
+        match (activity.object_type, activity.condition.classes) {
+            (Some(ObjectType::Content), Some(classes))
+            | (Some(ObjectType::Comment), Some(classes))
+                if !classes.is_empty() =>
+            {
+                let call = feed::ApplicationCall::CheckTags {
+                    cid: object_id,
+                    tags: classes,
+                };
+                self.call_application(true, Self::feed_app_id()?, &call, vec![])
+                    .await?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
     async fn content_author(&mut self, cid: String) -> Result<Owner, ActivityError> {
         let call = feed::ApplicationCall::ContentAuthor { cid };
         let (author, _) = self
@@ -331,6 +371,20 @@ impl Activity {
         Ok(())
     }
 
+    async fn open_activity_funds(&mut self, activity_id: u64) -> Result<(), ActivityError> {
+        let call = foundation::ApplicationCall::OpenActivity { activity_id };
+        self.call_application(true, Self::foundation_app_id()?, &call, vec![])
+            .await?;
+        Ok(())
+    }
+
+    async fn refund_activity_funds(&mut self, activity_id: u64) -> Result<(), ActivityError> {
+        let call = foundation::ApplicationCall::Refund { activity_id };
+        self.call_application(true, Self::foundation_app_id()?, &call, vec![])
+            .await?;
+        Ok(())
+    }
+
     async fn _finalize(&mut self, activity_id: u64) -> Result<(), ActivityError> {
         self.finalize(activity_id).await?;
         let activity = self.activity(activity_id).await?;
@@ -359,6 +413,7 @@ impl Activity {
             .await?;
         }
         self.reward_activity_host(activity_id).await?;
+        self.refund_activity_funds(activity_id).await?;
         Ok(())
     }
 }
@@ -62,9 +62,10 @@ pub enum ObjectType {
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
 pub struct ObjectCondition {
-    classes: Option<Vec<String>>,
-    min_words: u32,
-    max_words: u32,
+    /// Tags of feed content, candidates must have at least one of them
+    pub classes: Option<Vec<String>>,
+    pub min_words: u32,
+    pub max_words: u32,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq, InputObject)]
@@ -97,7 +97,9 @@ impl Contract for Credit {
                     .with_authenticated_message(dest, Message::Reward { owner, amount }))
             }
             Message::SetRewardCallers { application_ids } => {
-                if context.chain_id != system_api::current_application_id().creation.chain_id {
+                if context.message_id.chain_id
+                    != system_api::current_application_id().creation.chain_id
+                {
                     return Err(ContractError::OperationNotAllowed);
                 }
                 self.set_reward_callers(application_ids.clone()).await;
@@ -109,7 +111,9 @@ impl Contract for Credit {
                 ))
             }
             Message::SetTransferCallers { application_ids } => {
-                if context.chain_id != system_api::current_application_id().creation.chain_id {
+                if context.message_id.chain_id
+                    != system_api::current_application_id().creation.chain_id
+                {
                     return Err(ContractError::OperationNotAllowed);
                 }
                 self.set_transfer_callers(application_ids.clone()).await;
@@ -135,6 +139,13 @@ impl Contract for Credit {
                 Ok(ExecutionResult::default()
                     .with_authenticated_message(dest, Message::TransferExt { to, amount }))
             }
+            Message::Penalize { owner, amount } => {
+                self.penalize(owner, amount).await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::Penalize { owner, amount }))
+            }
             Message::RequestSubscribe => {
                 let mut result = ExecutionResult::default();
                 if context.message_id.chain_id
@@ -159,7 +170,7 @@ impl Contract for Credit {
 
     async fn handle_application_call(
         &mut self,
-        _context: &CalleeContext,
+        context: &CalleeContext,
         call: Self::ApplicationCall,
         _forwarded_sessions: Vec<SessionId>,
     ) -> Result<ApplicationCallResult<Self::Message, Self::Response, Self::SessionState>, Self::Error>
@@ -175,6 +186,16 @@ impl Contract for Credit {
                     system_api::current_application_id().creation.chain_id,
                     Message::Transfer { from, to, amount },
                 ),
+            ApplicationCall::Penalize { owner, amount } => {
+                match context.authenticated_caller_id {
+                    Some(caller_id) if self.is_reward_caller(caller_id).await? => {}
+                    _ => return Err(ContractError::CallerNotAllowed),
+                }
+                ExecutionResult::default().with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::Penalize { owner, amount },
+                )
+            }
         };
         let mut result = ApplicationCallResult::default();
         result.execution_result = execution_result;
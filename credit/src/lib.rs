@@ -80,6 +80,11 @@ pub enum ApplicationCall {
         to: Owner,
         amount: Amount,
     },
+    /// Take back spendable credits of owner to supply balance
+    Penalize {
+        owner: Owner,
+        amount: Amount,
+    },
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -101,6 +106,10 @@ pub enum Message {
         to: Owner,
         amount: Amount,
     },
+    Penalize {
+        owner: Owner,
+        amount: Amount,
+    },
     SetRewardCallers {
         application_ids: Vec<ApplicationId>,
     },
@@ -135,12 +135,46 @@ impl Credit {
         }
     }
 
+    // At most spendable credits are taken back, oldest credits first
+    pub(crate) async fn penalize(
+        &mut self,
+        owner: Owner,
+        amount: Amount,
+    ) -> Result<(), StateError> {
+        let spendable = self.spendables.get(&owner).await?.unwrap_or_default();
+        let amount = amount.min(spendable);
+        if amount == Amount::ZERO {
+            return Ok(());
+        }
+        self.spendables
+            .insert(&owner, spendable.saturating_sub(amount))?;
+        if let Some(mut amounts) = self.balances.get(&owner).await? {
+            let mut remain = amount;
+            amounts.amounts.retain_mut(|_amount| {
+                let taken = remain.min(_amount.amount);
+                _amount.amount = _amount.amount.saturating_sub(taken);
+                remain = remain.saturating_sub(taken);
+                _amount.amount > Amount::ZERO
+            });
+            self.balances.insert(&owner, amounts)?;
+        }
+        self.balance.set(self.balance.get().saturating_add(amount));
+        Ok(())
+    }
+
     pub(crate) async fn set_reward_callers(&mut self, application_ids: Vec<ApplicationId>) {
         application_ids
             .iter()
             .for_each(|application_id| self.reward_callers.insert(application_id).unwrap())
     }
 
+    pub(crate) async fn is_reward_caller(
+        &self,
+        application_id: ApplicationId,
+    ) -> Result<bool, StateError> {
+        Ok(self.reward_callers.contains(&application_id).await?)
+    }
+
     pub(crate) async fn set_transfer_callers(&mut self, application_ids: Vec<ApplicationId>) {
         application_ids
             .iter()
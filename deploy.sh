@@ -59,18 +59,11 @@ echo -e "    Application ID: $BLUE$credit_appid$NC"
 
 print $'\U01F4AB' $YELLOW " Deploying Foundation application ..."
 foundation_bid=`linera --with-wallet 0 publish-bytecode ./target/wasm32-unknown-unknown/release/foundation_{contract,service}.wasm`
-foundation_appid=`linera --with-wallet 0 create-application $foundation_bid --json-argument '{"review_reward_percent":20,"review_reward_factor":20,"author_reward_percent":40,"author_reward_factor":20,"activity_reward_percent":10}'`
+foundation_appid=`linera --with-wallet 0 create-application $foundation_bid --json-argument '{"review_reward_percent":20,"review_reward_factor":20,"author_reward_percent":40,"author_reward_factor":20,"activity_reward_percent":10,"reviewer_stipend_percent":10,"reviewer_stipend_epoch_ms":86400000}'`
 print $'\U01f499' $LIGHTGREEN " Foundation application deployed"
 echo -e "    Bytecode ID:    $BLUE$foundation_bid$NC"
 echo -e "    Application ID: $BLUE$foundation_appid$NC"
 
-print $'\U01F4AB' $YELLOW " Deploying Feed application ..."
-feed_bid=`linera --with-wallet 0 publish-bytecode ./target/wasm32-unknown-unknown/release/feed_{contract,service}.wasm`
-feed_appid=`linera --with-wallet 0 create-application $feed_bid --json-argument '{"react_interval_ms":60000}' --json-parameters "{\"credit_app_id\":\"$credit_appid\",\"foundation_app_id\":\"$foundation_appid\"}" --required-application-ids $credit_appid --required-application-ids $foundation_appid`
-print $'\U01f499' $LIGHTGREEN " Feed application deployed"
-echo -e "    Bytecode ID:    $BLUE$feed_bid$NC"
-echo -e "    Application ID: $BLUE$feed_appid$NC"
-
 print $'\U01F4AB' $YELLOW " Deploying Market application ..."
 market_bid=`linera --with-wallet 0 publish-bytecode ./target/wasm32-unknown-unknown/release/market_{contract,service}.wasm`
 market_appid=`linera --with-wallet 0 create-application $market_bid --json-argument '{"credits_per_linera":"30","max_credits_percent":30,"trade_fee_percent":3}' --json-parameters "{\"credit_app_id\":\"$credit_appid\",\"foundation_app_id\":\"$foundation_appid\"}" --required-application-ids $credit_appid --required-application-ids $foundation_appid`
@@ -78,9 +71,16 @@ print $'\U01f499' $LIGHTGREEN " Market application deployed"
 echo -e "    Bytecode ID:    $BLUE$market_bid$NC"
 echo -e "    Application ID: $BLUE$market_appid$NC"
 
+print $'\U01F4AB' $YELLOW " Deploying Feed application ..."
+feed_bid=`linera --with-wallet 0 publish-bytecode ./target/wasm32-unknown-unknown/release/feed_{contract,service}.wasm`
+feed_appid=`linera --with-wallet 0 create-application $feed_bid --json-argument '{"rate_limits":{"react":{"window_ms":60000,"max_actions":10},"comment":{"window_ms":60000,"max_actions":5},"publish":{"window_ms":3600000,"max_actions":10},"tip":{"window_ms":60000,"max_actions":10}},"tip_fee_percent":5,"max_comment_depth":8,"edit_requires_review":true,"clawback_author_reward":true,"hot_gravity_secs":45000,"verify_content_cid":false,"max_body_bytes":65536,"offload_body":false,"excerpt_bytes":1024,"report_threshold":5,"bad_report_penalty":"50.0"}' --json-parameters "{\"credit_app_id\":\"$credit_appid\",\"foundation_app_id\":\"$foundation_appid\",\"market_app_id\":\"$market_appid\"}" --required-application-ids $credit_appid --required-application-ids $foundation_appid --required-application-ids $market_appid`
+print $'\U01f499' $LIGHTGREEN " Feed application deployed"
+echo -e "    Bytecode ID:    $BLUE$feed_bid$NC"
+echo -e "    Application ID: $BLUE$feed_appid$NC"
+
 print $'\U01F4AB' $YELLOW " Deploying Review application ..."
 review_bid=`linera --with-wallet 0 publish-bytecode ./target/wasm32-unknown-unknown/release/review_{contract,service}.wasm`
-review_appid=`linera --with-wallet 0 create-application $review_bid --json-argument '{"content_approved_threshold":3,"content_rejected_threshold":2,"asset_approved_threshold":2,"asset_rejected_threshold":2,"reviewer_approved_threshold":2,"reviewer_rejected_threshold":2,"activity_approved_threshold":2,"activity_rejected_threshold":2}' --json-parameters "{\"feed_app_id\":\"$feed_appid\",\"credit_app_id\":\"$credit_appid\",\"foundation_app_id\":\"$foundation_appid\",\"market_app_id\":\"$market_appid\"}" --required-application-ids $feed_appid --required-application-ids $credit_appid --required-application-ids $foundation_appid --required-application-ids $market_appid`
+review_appid=`linera --with-wallet 0 create-application $review_bid --json-argument '{"content_approved_threshold":3,"content_rejected_threshold":2,"asset_approved_threshold":2,"asset_rejected_threshold":2,"reviewer_approved_threshold":2,"reviewer_rejected_threshold":2,"activity_approved_threshold":2,"activity_rejected_threshold":2,"content_takedown_threshold":2,"verify_content_cid":false,"max_body_bytes":65536}' --json-parameters "{\"feed_app_id\":\"$feed_appid\",\"credit_app_id\":\"$credit_appid\",\"foundation_app_id\":\"$foundation_appid\",\"market_app_id\":\"$market_appid\"}" --required-application-ids $feed_appid --required-application-ids $credit_appid --required-application-ids $foundation_appid --required-application-ids $market_appid`
 print $'\U01f499' $LIGHTGREEN " Review application deployed"
 echo -e "    Bytecode ID:    $BLUE$review_bid$NC"
 echo -e "    Application ID: $BLUE$review_appid$NC"
@@ -113,6 +113,22 @@ for i in `seq 0 $EXTRA_WALLET_NUMBER`; do
   run_new_service $i
 done
 
+print $'\U01F4AB' $YELLOW " Allowing Review application to call Feed ..."
+while true; do
+  default_chain=`curl -s http://localhost:9080 -H 'Content-Type: application/json' -d '{"query":"query { chains { default } }"}' | sed -n 's/.*"default":"\([0-9a-f]*\)".*/\1/p'`
+  [ -z "$default_chain" ] && sleep 3 && continue
+  break
+done
+curl -s http://localhost:9080/chains/$default_chain/applications/$feed_appid -H 'Content-Type: application/json' -d "{\"query\":\"mutation { setReviewApp(applicationId: \\\"$review_appid\\\") }\"}" > /dev/null
+print $'\U01F4AB' $YELLOW " Allowing Feed application to penalize credits ..."
+curl -s http://localhost:9080/chains/$default_chain/applications/$credit_appid -H 'Content-Type: application/json' -d "{\"query\":\"mutation { setRewardCallers(applicationIds: [\\\"$feed_appid\\\"]) }\"}" > /dev/null
+print $'\U01F4AB' $YELLOW " Allowing Feed application to reward and clawback authors ..."
+curl -s http://localhost:9080/chains/$default_chain/applications/$foundation_appid -H 'Content-Type: application/json' -d "{\"query\":\"mutation { setAuthorRewardCallers(applicationIds: [\\\"$feed_appid\\\"]) }\"}" > /dev/null
+print $'\U01F4AB' $YELLOW " Allowing Review application to record reviews ..."
+curl -s http://localhost:9080/chains/$default_chain/applications/$foundation_appid -H 'Content-Type: application/json' -d "{\"query\":\"mutation { setReviewCallers(applicationIds: [\\\"$review_appid\\\"]) }\"}" > /dev/null
+print $'\U01F4AB' $YELLOW " Allowing Activity and Review applications to open and refund activities ..."
+curl -s http://localhost:9080/chains/$default_chain/applications/$foundation_appid -H 'Content-Type: application/json' -d "{\"query\":\"mutation { setActivityCallers(applicationIds: [\\\"$activity_appid\\\", \\\"$review_appid\\\"]) }\"}" > /dev/null
+
 function cleanup() {
   killall -15 linera > /dev/null 2>&1
   killall -15 linera-proxy > /dev/null 2>&1
@@ -4,10 +4,13 @@ mod state;
 
 use std::collections::HashMap;
 
-use self::state::Feed;
+use self::state::{Feed, StateError};
 use async_trait::async_trait;
 use credit::CreditAbi;
-use feed::{ApplicationCall, Content, Message, Operation};
+use feed::{
+    split_amount, Action, ApplicationCall, AuthorShare, Avatar, BodyStorage, Content, Message,
+    Operation, ReactionKind, ReviewCall, ReviewCallAbi,
+};
 use foundation::FoundationAbi;
 use linera_sdk::{
     base::{Amount, ApplicationId, ChannelName, Destination, Owner, SessionId, WithContractAbi},
@@ -15,6 +18,7 @@ use linera_sdk::{
     ApplicationCallResult, CalleeContext, Contract, ExecutionResult, MessageContext,
     OperationContext, SessionCallResult, ViewStateStorage,
 };
+use market::MarketAbi;
 use thiserror::Error;
 
 linera_sdk::contract!(Feed);
@@ -24,6 +28,7 @@ impl WithContractAbi for Feed {
 }
 
 const SUBSCRIPTION_CHANNEL: &[u8] = b"subscriptions";
+const BACKFILL_PAGE_SIZE: usize = 10;
 
 #[async_trait]
 impl Contract for Feed {
@@ -35,7 +40,7 @@ impl Contract for Feed {
         _context: &OperationContext,
         state: Self::InitializationArgument,
     ) -> Result<ExecutionResult<Self::Message>, Self::Error> {
-        self.initialize_feed(state).await;
+        self.initialize_feed(state).await?;
         Ok(ExecutionResult::default())
     }
 
@@ -47,22 +52,188 @@ impl Contract for Feed {
         match operation {
             Operation::Like { cid } => Ok(ExecutionResult::default().with_authenticated_message(
                 system_api::current_application_id().creation.chain_id,
-                Message::Like { cid },
+                Message::React {
+                    cid,
+                    kind: ReactionKind::Like,
+                },
             )),
             Operation::Dislike { cid } => Ok(ExecutionResult::default()
                 .with_authenticated_message(
                     system_api::current_application_id().creation.chain_id,
-                    Message::Dislike { cid },
+                    Message::React {
+                        cid,
+                        kind: ReactionKind::Dislike,
+                    },
+                )),
+            Operation::React { cid, kind } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::React { cid, kind },
+                )),
+            Operation::Unreact { cid } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::Unreact { cid },
+                )),
+            Operation::Tip {
+                cid,
+                amount,
+                credits,
+            } => Ok(ExecutionResult::default().with_authenticated_message(
+                system_api::current_application_id().creation.chain_id,
+                Message::Tip {
+                    cid,
+                    amount,
+                    credits,
+                },
+            )),
+            Operation::SetReviewApp { application_id } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::SetReviewApp { application_id },
+                )),
+            Operation::Edit {
+                cid,
+                new_cid,
+                title,
+                content,
+            } => Ok(ExecutionResult::default().with_authenticated_message(
+                system_api::current_application_id().creation.chain_id,
+                Message::Edit {
+                    cid,
+                    new_cid,
+                    title,
+                    content,
+                },
+            )),
+            Operation::Delete { cid, reason } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::Delete { cid, reason },
+                )),
+            Operation::Follow { author } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::Follow { author },
+                )),
+            Operation::Unfollow { author } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::Unfollow { author },
+                )),
+            Operation::UpdateProfile {
+                display_name,
+                bio,
+                links,
+                avatar,
+            } => Ok(ExecutionResult::default().with_authenticated_message(
+                system_api::current_application_id().creation.chain_id,
+                Message::UpdateProfile {
+                    display_name,
+                    bio,
+                    links,
+                    avatar,
+                },
+            )),
+            Operation::Bookmark { cid } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::Bookmark { cid },
+                )),
+            Operation::Unbookmark { cid } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::Unbookmark { cid },
+                )),
+            Operation::CreateReadingList {
+                name,
+                description,
+                public,
+            } => Ok(ExecutionResult::default().with_authenticated_message(
+                system_api::current_application_id().creation.chain_id,
+                Message::CreateReadingList {
+                    name,
+                    description,
+                    public,
+                },
+            )),
+            Operation::DeleteReadingList { list_id } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::DeleteReadingList { list_id },
+                )),
+            Operation::AddToReadingList { list_id, cid } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::AddToReadingList { list_id, cid },
+                )),
+            Operation::RemoveFromReadingList { list_id, cid } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::RemoveFromReadingList { list_id, cid },
+                )),
+            Operation::MoveReadingListItem {
+                list_id,
+                cid,
+                position,
+            } => Ok(ExecutionResult::default().with_authenticated_message(
+                system_api::current_application_id().creation.chain_id,
+                Message::MoveReadingListItem {
+                    list_id,
+                    cid,
+                    position,
+                },
+            )),
+            Operation::SetPremium { cid, price } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::SetPremium { cid, price },
+                )),
+            Operation::Unlock { cid } => Ok(ExecutionResult::default().with_authenticated_message(
+                system_api::current_application_id().creation.chain_id,
+                Message::Unlock { cid },
+            )),
+            Operation::Report { cid, reason } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::Report { cid, reason },
                 )),
-            Operation::Tip { cid, amount } => Ok(ExecutionResult::default()
+            Operation::CreateSeries { title, description } => Ok(ExecutionResult::default()
                 .with_authenticated_message(
                     system_api::current_application_id().creation.chain_id,
-                    Message::Tip { cid, amount },
+                    Message::CreateSeries { title, description },
                 )),
-            Operation::RequestSubscribe => Ok(ExecutionResult::default()
+            Operation::AppendToSeries { series_id, cid } => Ok(ExecutionResult::default()
                 .with_authenticated_message(
                     system_api::current_application_id().creation.chain_id,
-                    Message::RequestSubscribe,
+                    Message::AppendToSeries { series_id, cid },
+                )),
+            Operation::RemoveFromSeries { series_id, cid } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::RemoveFromSeries { series_id, cid },
+                )),
+            Operation::MoveSeriesItem {
+                series_id,
+                cid,
+                position,
+            } => Ok(ExecutionResult::default().with_authenticated_message(
+                system_api::current_application_id().creation.chain_id,
+                Message::MoveSeriesItem {
+                    series_id,
+                    cid,
+                    position,
+                },
+            )),
+            Operation::RequestBackfill { cursor } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::RequestBackfill { cursor },
+                )),
+            Operation::RequestSubscribe { topics } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::RequestSubscribe { topics },
                 )),
         }
     }
@@ -73,90 +244,135 @@ impl Contract for Feed {
         message: Self::Message,
     ) -> Result<ExecutionResult<Self::Message>, Self::Error> {
         match message {
-            Message::Like { cid } => {
-                self.like(
+            Message::React { cid, kind } => {
+                let topic = self.content_topic(cid.clone()).await?;
+                self.react(
                     cid.clone(),
                     context.authenticated_signer.unwrap(),
+                    kind,
                     context.chain_id == system_api::current_application_id().creation.chain_id,
                 )
                 .await?;
+                self.broadcast(topic, Message::React { cid, kind }).await
+            }
+            Message::Unreact { cid } => {
+                let topic = self.content_topic(cid.clone()).await?;
+                let owner = context.authenticated_signer.unwrap();
+                if context.chain_id == system_api::current_application_id().creation.chain_id {
+                    self.rate_limit(owner, Action::React).await?;
+                }
+                self.unreact_content(cid.clone(), owner).await?;
+                self.broadcast(topic, Message::Unreact { cid }).await
+            }
+            Message::SetReviewApp { application_id } => {
+                if context.message_id.chain_id
+                    != system_api::current_application_id().creation.chain_id
+                {
+                    return Err(ContractError::OperationNotAllowed);
+                }
+                self.review_app_id.set(Some(application_id));
                 let dest =
                     Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
                 Ok(ExecutionResult::default()
-                    .with_authenticated_message(dest, Message::Like { cid }))
+                    .with_authenticated_message(dest, Message::SetReviewApp { application_id }))
             }
-            Message::Dislike { cid } => {
-                self.dislike(
+            Message::Tip {
+                cid,
+                amount,
+                credits,
+            } => {
+                let topic = self.content_topic(cid.clone()).await?;
+                self.tip(
                     cid.clone(),
                     context.authenticated_signer.unwrap(),
+                    amount,
+                    credits,
                     context.chain_id == system_api::current_application_id().creation.chain_id,
                 )
                 .await?;
-                let dest =
-                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
-                Ok(ExecutionResult::default()
-                    .with_authenticated_message(dest, Message::Dislike { cid }))
-            }
-            Message::Tip { cid, amount } => {
-                let dest =
-                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
-                Ok(ExecutionResult::default()
-                    .with_authenticated_message(dest, Message::Tip { cid, amount }))
+                self.broadcast(
+                    topic,
+                    Message::Tip {
+                        cid,
+                        amount,
+                        credits,
+                    },
+                )
+                .await
             }
             Message::Publish {
                 cid,
                 title,
                 content,
+                body_storage,
+                body_size,
                 author,
+                tags,
+                authors,
             } => {
                 self.publish(
                     cid.clone(),
                     None,
                     title.clone(),
-                    content.clone(),
+                    content,
+                    body_storage,
+                    body_size,
+                    tags.clone(),
                     author,
+                    authors.clone(),
                     context.chain_id == system_api::current_application_id().creation.chain_id,
                 )
                 .await?;
-                let dest =
-                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
-                Ok(ExecutionResult::default().with_authenticated_message(
-                    dest,
+                // Subscribers get what is stored, which is an excerpt if the body is offloaded
+                let (content, body_storage, body_size) = self.stored_body(cid.clone()).await?;
+                let topic = self.content_topic(cid.clone()).await?;
+                self.broadcast(
+                    topic,
                     Message::Publish {
                         cid,
                         title,
                         content,
+                        body_storage,
+                        body_size,
                         author,
+                        tags,
+                        authors,
                     },
-                ))
+                )
+                .await
             }
             Message::Recommend {
                 cid,
                 reason_cid,
                 reason,
+                recommender,
             } => {
-                let author = context.authenticated_signer.unwrap();
+                let topic = self.content_topic(cid.clone()).await?;
                 self.publish(
                     reason_cid.clone(),
                     Some(cid.clone()),
                     String::default(),
                     reason.clone(),
-                    author,
+                    BodyStorage::Inline,
+                    0,
+                    Vec::new(),
+                    recommender,
+                    Vec::new(),
                     context.chain_id == system_api::current_application_id().creation.chain_id,
                 )
                 .await?;
                 self.recommend_content(cid.clone(), reason_cid.clone())
                     .await?;
-                let dest =
-                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
-                Ok(ExecutionResult::default().with_authenticated_message(
-                    dest,
+                self.broadcast(
+                    topic,
                     Message::Recommend {
                         cid,
                         reason_cid,
                         reason,
+                        recommender,
                     },
-                ))
+                )
+                .await
             }
             Message::Comment {
                 cid,
@@ -164,48 +380,382 @@ impl Contract for Feed {
                 comment,
                 commentor,
             } => {
+                let topic = self.content_topic(cid.clone()).await?;
+                let cid = self.reply_target(cid).await?;
                 self.publish(
                     comment_cid.clone(),
                     Some(cid.clone()),
                     String::default(),
                     comment.clone(),
+                    BodyStorage::Inline,
+                    0,
+                    Vec::new(),
                     commentor,
+                    Vec::new(),
                     context.chain_id == system_api::current_application_id().creation.chain_id,
                 )
                 .await?;
                 self.comment_content(cid.clone(), comment_cid.clone())
                     .await?;
-                let dest =
-                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
-                Ok(ExecutionResult::default().with_authenticated_message(
-                    dest,
+                self.broadcast(
+                    topic,
                     Message::Comment {
                         cid,
                         comment_cid,
                         comment,
                         commentor,
                     },
+                )
+                .await
+            }
+            Message::Edit {
+                cid,
+                new_cid,
+                title,
+                content,
+            } => {
+                let topic = self.content_topic(cid.clone()).await?;
+                self.edit_content(
+                    cid.clone(),
+                    new_cid.clone(),
+                    title.clone(),
+                    content.clone(),
+                    context.authenticated_signer.unwrap(),
+                    false,
+                )
+                .await?;
+                self.broadcast(
+                    topic,
+                    Message::Edit {
+                        cid,
+                        new_cid,
+                        title,
+                        content,
+                    },
+                )
+                .await
+            }
+            Message::ReviewedEdit {
+                cid,
+                new_cid,
+                title,
+                content,
+                author,
+            } => {
+                let topic = self.content_topic(cid.clone()).await?;
+                self.edit_content(
+                    cid.clone(),
+                    new_cid.clone(),
+                    title.clone(),
+                    content.clone(),
+                    author,
+                    true,
+                )
+                .await?;
+                self.broadcast(
+                    topic,
+                    Message::ReviewedEdit {
+                        cid,
+                        new_cid,
+                        title,
+                        content,
+                        author,
+                    },
+                )
+                .await
+            }
+            Message::Delete { cid, reason } => {
+                let topic = self.content_topic(cid.clone()).await?;
+                self.remove(
+                    cid.clone(),
+                    reason.clone(),
+                    Some(context.authenticated_signer.unwrap()),
+                    context.chain_id == system_api::current_application_id().creation.chain_id,
+                )
+                .await?;
+                self.broadcast(topic, Message::Delete { cid, reason }).await
+            }
+            Message::Takedown { cid, reason } => {
+                let topic = self.content_topic(cid.clone()).await?;
+                self.remove(
+                    cid.clone(),
+                    reason.clone(),
+                    None,
+                    context.chain_id == system_api::current_application_id().creation.chain_id,
+                )
+                .await?;
+                self.broadcast(topic, Message::Takedown { cid, reason })
+                    .await
+            }
+            Message::Follow { author } => {
+                let topic = Some((author, Vec::new()));
+                self.follow(context.authenticated_signer.unwrap(), author)
+                    .await?;
+                self.broadcast(topic, Message::Follow { author }).await
+            }
+            Message::Unfollow { author } => {
+                let topic = Some((author, Vec::new()));
+                self.unfollow(context.authenticated_signer.unwrap(), author)
+                    .await?;
+                self.broadcast(topic, Message::Unfollow { author }).await
+            }
+            Message::UpdateProfile {
+                display_name,
+                bio,
+                links,
+                avatar,
+            } => {
+                let owner = context.authenticated_signer.unwrap();
+                if let Some(avatar) = avatar {
+                    if context.chain_id == system_api::current_application_id().creation.chain_id {
+                        self.validate_avatar(owner, avatar).await?;
+                    }
+                }
+                self.update_profile(
+                    owner,
+                    display_name.clone(),
+                    bio.clone(),
+                    links.clone(),
+                    avatar,
+                )
+                .await?;
+                let topic = Some((owner, Vec::new()));
+                self.broadcast(
+                    topic,
+                    Message::UpdateProfile {
+                        display_name,
+                        bio,
+                        links,
+                        avatar,
+                    },
+                )
+                .await
+            }
+            Message::Bookmark { cid } => {
+                let owner = context.authenticated_signer.unwrap();
+                self.bookmark(owner, cid.clone()).await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::Bookmark { cid }))
+            }
+            Message::Unbookmark { cid } => {
+                let owner = context.authenticated_signer.unwrap();
+                self.unbookmark(owner, cid.clone()).await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::Unbookmark { cid }))
+            }
+            Message::CreateReadingList {
+                name,
+                description,
+                public,
+            } => {
+                let owner = context.authenticated_signer.unwrap();
+                self.create_reading_list(owner, name.clone(), description.clone(), public)
+                    .await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default().with_authenticated_message(
+                    dest,
+                    Message::CreateReadingList {
+                        name,
+                        description,
+                        public,
+                    },
                 ))
             }
-            Message::RequestSubscribe => {
-                let mut result = ExecutionResult::default();
-                if context.message_id.chain_id
-                    == system_api::current_application_id().creation.chain_id
+            Message::DeleteReadingList { list_id } => {
+                let owner = context.authenticated_signer.unwrap();
+                self.delete_reading_list(owner, list_id).await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::DeleteReadingList { list_id }))
+            }
+            Message::AddToReadingList { list_id, cid } => {
+                let owner = context.authenticated_signer.unwrap();
+                self.add_to_reading_list(owner, list_id, cid.clone())
+                    .await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::AddToReadingList { list_id, cid }))
+            }
+            Message::RemoveFromReadingList { list_id, cid } => {
+                let owner = context.authenticated_signer.unwrap();
+                self.remove_from_reading_list(owner, list_id, cid.clone())
+                    .await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default().with_authenticated_message(
+                    dest,
+                    Message::RemoveFromReadingList { list_id, cid },
+                ))
+            }
+            Message::MoveReadingListItem {
+                list_id,
+                cid,
+                position,
+            } => {
+                let owner = context.authenticated_signer.unwrap();
+                self.move_reading_list_item(owner, list_id, cid.clone(), position)
+                    .await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default().with_authenticated_message(
+                    dest,
+                    Message::MoveReadingListItem {
+                        list_id,
+                        cid,
+                        position,
+                    },
+                ))
+            }
+            Message::SetPremium { cid, price } => {
+                let topic = self.content_topic(cid.clone()).await?;
+                self.set_premium(cid.clone(), context.authenticated_signer.unwrap(), price)
+                    .await?;
+                self.broadcast(topic, Message::SetPremium { cid, price })
+                    .await
+            }
+            Message::Unlock { cid } => {
+                let topic = self.content_topic(cid.clone()).await?;
+                let reader = context.authenticated_signer.unwrap();
+                let price = self.unlock_content(cid.clone(), reader).await?;
+                if context.chain_id == system_api::current_application_id().creation.chain_id {
+                    let shares = self.content_shares(cid.clone()).await?;
+                    self.pay_authors(reader, shares, price.amount, price.credits)
+                        .await?;
+                }
+                self.broadcast(topic, Message::Unlock { cid }).await
+            }
+            Message::Report { cid, reason } => {
+                let topic = self.content_topic(cid.clone()).await?;
+                let flagged = self
+                    .report_content(
+                        cid.clone(),
+                        context.authenticated_signer.unwrap(),
+                        reason.clone(),
+                    )
+                    .await?;
+                if let Some(reasons) = flagged {
+                    if context.chain_id == system_api::current_application_id().creation.chain_id {
+                        self.open_review_takedown(cid.clone(), reasons).await?;
+                    }
+                }
+                self.broadcast(topic, Message::Report { cid, reason }).await
+            }
+            Message::DismissReports { cid } => {
+                let topic = self.content_topic(cid.clone()).await?;
+                let reporters = self.dismiss_reports(cid.clone()).await?;
+                let penalty = *self.bad_report_penalty.get();
+                if context.chain_id == system_api::current_application_id().creation.chain_id
+                    && penalty > Amount::ZERO
                 {
+                    for reporter in reporters {
+                        self.penalize_credits(reporter, penalty).await?;
+                    }
+                }
+                self.broadcast(topic, Message::DismissReports { cid }).await
+            }
+            Message::CreateSeries { title, description } => {
+                let author = context.authenticated_signer.unwrap();
+                self.create_series(author, title.clone(), description.clone())
+                    .await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::CreateSeries { title, description }))
+            }
+            Message::AppendToSeries { series_id, cid } => {
+                let author = context.authenticated_signer.unwrap();
+                self.append_to_series(author, series_id, cid.clone())
+                    .await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::AppendToSeries { series_id, cid }))
+            }
+            Message::RemoveFromSeries { series_id, cid } => {
+                let author = context.authenticated_signer.unwrap();
+                self.remove_from_series(author, series_id, cid.clone())
+                    .await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::RemoveFromSeries { series_id, cid }))
+            }
+            Message::MoveSeriesItem {
+                series_id,
+                cid,
+                position,
+            } => {
+                let author = context.authenticated_signer.unwrap();
+                self.move_series_item(author, series_id, cid.clone(), position)
+                    .await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default().with_authenticated_message(
+                    dest,
+                    Message::MoveSeriesItem {
+                        series_id,
+                        cid,
+                        position,
+                    },
+                ))
+            }
+            Message::RequestSubscribe { topics } => {
+                let mut result = ExecutionResult::default();
+                let chain_id = context.message_id.chain_id;
+                if chain_id == system_api::current_application_id().creation.chain_id {
                     return Ok(result);
                 }
-                result.subscribe.push((
-                    ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()),
-                    context.message_id.chain_id,
-                ));
-                Ok(result)
+                // Topic subscribers leave the full replication channel
+                let channel = ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec());
+                match topics.is_empty() {
+                    true => result.subscribe.push((channel, chain_id)),
+                    false => result.unsubscribe.push((channel, chain_id)),
+                }
+                self.subscribe_topics(chain_id, topics).await?;
+                let (contents, cursor) = self
+                    .backfill_page(chain_id, BACKFILL_PAGE_SIZE, None)
+                    .await?;
+                Ok(result
+                    .with_authenticated_message(
+                        chain_id,
+                        Message::InitialState {
+                            state: self.initial_state().await?,
+                        },
+                    )
+                    .with_authenticated_message(chain_id, Message::Backfill { contents, cursor }))
+            }
+            Message::RequestBackfill { cursor } => {
+                let chain_id = context.message_id.chain_id;
+                if chain_id == system_api::current_application_id().creation.chain_id {
+                    return Ok(ExecutionResult::default());
+                }
+                let (contents, cursor) = self
+                    .backfill_page(chain_id, BACKFILL_PAGE_SIZE, Some(cursor))
+                    .await?;
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(chain_id, Message::Backfill { contents, cursor }))
+            }
+            Message::InitialState { state } => {
+                self.initialize_feed(state).await?;
+                Ok(ExecutionResult::default())
+            }
+            Message::Backfill { contents, cursor } => {
+                self.backfill(contents, cursor).await?;
+                Ok(ExecutionResult::default())
             }
         }
     }
 
     async fn handle_application_call(
         &mut self,
-        _context: &CalleeContext,
+        context: &CalleeContext,
         call: Self::ApplicationCall,
         _forwarded_sessions: Vec<SessionId>,
     ) -> Result<ApplicationCallResult<Self::Message, Self::Response, Self::SessionState>, Self::Error>
@@ -215,6 +765,7 @@ impl Contract for Feed {
                 cid,
                 reason_cid,
                 reason,
+                recommender,
             } => Ok(ApplicationCallResult {
                 value: None,
                 execution_result: ExecutionResult::default().with_authenticated_message(
@@ -223,6 +774,7 @@ impl Contract for Feed {
                         cid,
                         reason_cid,
                         reason,
+                        recommender,
                     },
                 ),
                 create_sessions: vec![],
@@ -250,6 +802,8 @@ impl Contract for Feed {
                 title,
                 content,
                 author,
+                tags,
+                authors,
             } => Ok(ApplicationCallResult {
                 value: None,
                 execution_result: ExecutionResult::default().with_authenticated_message(
@@ -257,17 +811,83 @@ impl Contract for Feed {
                     Message::Publish {
                         cid,
                         title,
+                        body_size: content.len() as u64,
                         content,
+                        body_storage: BodyStorage::Inline,
                         author,
+                        tags,
+                        authors,
                     },
                 ),
                 create_sessions: vec![],
             }),
+            ApplicationCall::Edit {
+                cid,
+                new_cid,
+                title,
+                content,
+                author,
+            } => {
+                self.check_review_caller(context)?;
+                Ok(ApplicationCallResult {
+                    value: None,
+                    execution_result: ExecutionResult::default().with_authenticated_message(
+                        system_api::current_application_id().creation.chain_id,
+                        Message::ReviewedEdit {
+                            cid,
+                            new_cid,
+                            title,
+                            content,
+                            author,
+                        },
+                    ),
+                    create_sessions: vec![],
+                })
+            }
+            ApplicationCall::Takedown { cid, reason } => {
+                self.check_review_caller(context)?;
+                Ok(ApplicationCallResult {
+                    value: None,
+                    execution_result: ExecutionResult::default().with_authenticated_message(
+                        system_api::current_application_id().creation.chain_id,
+                        Message::Takedown { cid, reason },
+                    ),
+                    create_sessions: vec![],
+                })
+            }
+            ApplicationCall::DismissReports { cid } => {
+                self.check_review_caller(context)?;
+                Ok(ApplicationCallResult {
+                    value: None,
+                    execution_result: ExecutionResult::default().with_authenticated_message(
+                        system_api::current_application_id().creation.chain_id,
+                        Message::DismissReports { cid },
+                    ),
+                    create_sessions: vec![],
+                })
+            }
+            ApplicationCall::CheckTags { cid, tags } => {
+                self.check_tags(cid, tags).await?;
+                Ok(ApplicationCallResult {
+                    value: None,
+                    execution_result: ExecutionResult::default(),
+                    create_sessions: vec![],
+                })
+            }
             ApplicationCall::ContentAuthor { cid } => Ok(ApplicationCallResult {
-                value: Some(self.content_author(cid).await?),
+                value: known_author(self.content_author(cid).await)?,
                 execution_result: ExecutionResult::default(),
                 create_sessions: vec![],
             }),
+            ApplicationCall::RateLimit { owner, action } => {
+                self.check_review_caller(context)?;
+                self.rate_limit(owner, action).await?;
+                Ok(ApplicationCallResult {
+                    value: None,
+                    execution_result: ExecutionResult::default(),
+                    create_sessions: vec![],
+                })
+            }
         }
     }
 
@@ -292,6 +912,56 @@ impl Feed {
         Ok(Self::parameters().unwrap().foundation_app_id)
     }
 
+    fn market_app_id() -> Result<ApplicationId<MarketAbi>, ContractError> {
+        Ok(Self::parameters().unwrap().market_app_id)
+    }
+
+    // Full subscribers get every message from the channel, topic subscribers only matching ones
+    async fn broadcast(
+        &self,
+        topic: Option<(Owner, Vec<String>)>,
+        message: Message,
+    ) -> Result<ExecutionResult<Message>, ContractError> {
+        let mut result = ExecutionResult::default();
+        if let Some((author, tags)) = topic {
+            for chain_id in self.topic_subscribers(author, tags).await? {
+                result = result.with_authenticated_message(chain_id, message.clone());
+            }
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        Ok(result.with_authenticated_message(dest, message))
+    }
+
+    async fn open_review_takedown(
+        &mut self,
+        cid: String,
+        reasons: Vec<String>,
+    ) -> Result<(), ContractError> {
+        let review_app_id = match *self.review_app_id.get() {
+            Some(application_id) => application_id.with_abi::<ReviewCallAbi>(),
+            None => return Ok(()),
+        };
+        let call = ReviewCall::OpenTakedown { cid, reasons };
+        self.call_application(true, review_app_id, &call, vec![])
+            .await?;
+        Ok(())
+    }
+
+    async fn validate_avatar(&mut self, owner: Owner, avatar: Avatar) -> Result<(), ContractError> {
+        let call = market::ApplicationCall::ValidateTokenOwner {
+            owner,
+            collection_id: avatar.collection_id,
+            token_id: avatar.token_id,
+        };
+        self.call_application(true, Self::market_app_id()?, &call, vec![])
+            .await?;
+        Ok(())
+    }
+
+    fn check_review_caller(&self, context: &CalleeContext) -> Result<(), ContractError> {
+        check_caller(*self.review_app_id.get(), context)
+    }
+
     async fn reward_credits(&mut self, owner: Owner, amount: Amount) -> Result<(), ContractError> {
         let call = credit::ApplicationCall::Reward { owner, amount };
         self.call_application(true, Self::credit_app_id()?, &call, vec![])
@@ -299,38 +969,163 @@ impl Feed {
         Ok(())
     }
 
-    async fn reward_tokens(&mut self, author: Owner) -> Result<(), ContractError> {
-        let call = foundation::ApplicationCall::Reward {
-            reward_user: Some(author),
-            reward_type: foundation::RewardType::Publish,
-            activity_id: None,
+    async fn penalize_credits(
+        &mut self,
+        owner: Owner,
+        amount: Amount,
+    ) -> Result<(), ContractError> {
+        let call = credit::ApplicationCall::Penalize { owner, amount };
+        self.call_application(true, Self::credit_app_id()?, &call, vec![])
+            .await?;
+        Ok(())
+    }
+
+    async fn clawback_tokens(&mut self, cid: String) -> Result<(), ContractError> {
+        let call = foundation::ApplicationCall::Clawback { object_id: cid };
+        self.call_application(true, Self::foundation_app_id()?, &call, vec![])
+            .await?;
+        Ok(())
+    }
+
+    async fn reward_tokens(
+        &mut self,
+        cid: String,
+        shares: Vec<AuthorShare>,
+    ) -> Result<(), ContractError> {
+        let call = foundation::ApplicationCall::RewardAuthors {
+            object_id: cid,
+            shares: shares
+                .into_iter()
+                .map(|share| (share.owner, share.percent))
+                .collect(),
         };
         self.call_application(true, Self::foundation_app_id()?, &call, vec![])
             .await?;
         Ok(())
     }
 
+    async fn transfer_credits(
+        &mut self,
+        from: Owner,
+        to: Owner,
+        amount: Amount,
+    ) -> Result<(), ContractError> {
+        let call = credit::ApplicationCall::Transfer { from, to, amount };
+        self.call_application(true, Self::credit_app_id()?, &call, vec![])
+            .await?;
+        Ok(())
+    }
+
+    async fn transfer_tokens(
+        &mut self,
+        from: Owner,
+        to: Owner,
+        amount: Amount,
+    ) -> Result<(), ContractError> {
+        let call = foundation::ApplicationCall::Transfer { from, to, amount };
+        self.call_application(true, Self::foundation_app_id()?, &call, vec![])
+            .await?;
+        Ok(())
+    }
+
+    async fn deposit_commission(
+        &mut self,
+        from: Owner,
+        amount: Amount,
+    ) -> Result<(), ContractError> {
+        let call = foundation::ApplicationCall::Deposit { from, amount };
+        self.call_application(true, Self::foundation_app_id()?, &call, vec![])
+            .await?;
+        Ok(())
+    }
+
+    async fn tip(
+        &mut self,
+        cid: String,
+        tipper: Owner,
+        amount: Amount,
+        credits: Amount,
+        creation_chain: bool,
+    ) -> Result<(), ContractError> {
+        if creation_chain {
+            self.rate_limit(tipper, Action::Tip).await?;
+        }
+        self.tip_content(cid.clone(), tipper, amount, credits)
+            .await?;
+        if !creation_chain {
+            return Ok(());
+        }
+        let shares = self.content_shares(cid).await?;
+        self.pay_authors(tipper, shares, amount, credits).await
+    }
+
+    // Credits go to authors directly, tokens go to authors after platform fee, both split by shares
+    async fn pay_authors(
+        &mut self,
+        from: Owner,
+        shares: Vec<AuthorShare>,
+        amount: Amount,
+        credits: Amount,
+    ) -> Result<(), ContractError> {
+        if credits > Amount::ZERO {
+            for (author, credits) in split_amount(credits, &shares) {
+                if credits > Amount::ZERO {
+                    self.transfer_credits(from, author, credits).await?;
+                }
+            }
+        }
+        if amount > Amount::ZERO {
+            let fee = self.tip_fee(amount);
+            if fee > Amount::ZERO {
+                self.deposit_commission(from, fee).await?;
+            }
+            for (author, amount) in split_amount(amount.saturating_sub(fee), &shares) {
+                if amount > Amount::ZERO {
+                    self.transfer_tokens(from, author, amount).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     async fn publish(
         &mut self,
         cid: String,
         comment_to_cid: Option<String>,
         title: String,
         content: String,
+        body_storage: BodyStorage,
+        body_size: u64,
+        tags: Vec<String>,
         author: Owner,
+        authors: Vec<AuthorShare>,
         creation_chain: bool,
     ) -> Result<(), ContractError> {
         match self
             .create_content(
                 Content {
-                    cid,
+                    cid: cid.clone(),
                     comment_to_cid,
                     title,
                     content,
+                    body_storage,
+                    body_size,
+                    tags,
                     author,
+                    authors: authors.clone(),
                     likes: 0,
                     dislikes: 0,
                     accounts: HashMap::default(),
+                    reactions: HashMap::default(),
+                    reactors: HashMap::default(),
+                    depth: 0,
+                    replies: 0,
+                    engagement: 0,
+                    hot_score: 0,
+                    revision_cid: None,
+                    price: None,
                     created_at: current_system_time(),
+                    edited_at: None,
                 },
                 author,
             )
@@ -340,47 +1135,71 @@ impl Feed {
                 if !creation_chain {
                     return Ok(());
                 }
-                self.reward_credits(author, Amount::from_tokens(500))
-                    .await?;
-                self.reward_tokens(author).await?;
+                let shares = match authors.is_empty() {
+                    true => vec![AuthorShare {
+                        owner: author,
+                        percent: 100,
+                    }],
+                    false => authors,
+                };
+                for (owner, credits) in split_amount(Amount::from_tokens(500), &shares) {
+                    self.reward_credits(owner, credits).await?;
+                }
+                self.reward_tokens(cid, shares).await?;
                 Ok(())
             }
             Err(err) => Err(ContractError::StateError(err)),
         }
     }
 
-    async fn like(
+    async fn remove(
         &mut self,
         cid: String,
-        owner: Owner,
+        reason: String,
+        author: Option<Owner>,
         creation_chain: bool,
     ) -> Result<(), ContractError> {
-        match self.like_content(cid, owner, true).await {
-            Ok(_) => {
-                if !creation_chain {
-                    return Ok(());
-                }
-                return self.reward_credits(owner, Amount::from_tokens(100)).await;
-            }
-            Err(err) => return Err(ContractError::StateError(err)),
+        let cid = self.remove_content(cid, reason, author).await?;
+        if !creation_chain || !*self.clawback_author_reward.get() {
+            return Ok(());
         }
+        self.clawback_tokens(cid).await
     }
 
-    async fn dislike(
+    async fn react(
         &mut self,
         cid: String,
         owner: Owner,
+        kind: ReactionKind,
         creation_chain: bool,
     ) -> Result<(), ContractError> {
-        match self.like_content(cid, owner, false).await {
-            Ok(_) => {
-                if !creation_chain {
-                    return Ok(());
-                }
-                return self.reward_credits(owner, Amount::from_tokens(100)).await;
-            }
-            Err(err) => return Err(ContractError::StateError(err)),
+        if creation_chain {
+            self.rate_limit(owner, Action::React).await?;
+        }
+        let first_reaction = self.react_content(cid, owner, kind).await?;
+        if !creation_chain || !first_reaction {
+            return Ok(());
         }
+        self.reward_credits(owner, Amount::from_tokens(100)).await
+    }
+}
+
+fn check_caller(
+    allowed_app_id: Option<ApplicationId>,
+    context: &CalleeContext,
+) -> Result<(), ContractError> {
+    match (allowed_app_id, context.authenticated_caller_id) {
+        (Some(allowed_app_id), Some(caller_id)) if allowed_app_id == caller_id => Ok(()),
+        _ => Err(ContractError::InvalidCaller),
+    }
+}
+
+// Callers get no author of unknown content, other errors are still returned
+fn known_author(author: Result<Owner, StateError>) -> Result<Option<Owner>, ContractError> {
+    match author {
+        Ok(author) => Ok(Some(author)),
+        Err(StateError::InvalidContent) => Ok(None),
+        Err(err) => Err(ContractError::StateError(err)),
     }
 }
 
@@ -399,8 +1218,78 @@ pub enum ContractError {
     InvalidPublisher,
 
     #[error(transparent)]
-    StateError(#[from] state::StateError),
+    StateError(#[from] StateError),
 
     #[error("Cross-application sessions not supported")]
     SessionsNotSupported,
+
+    #[error("Operation not allowed")]
+    OperationNotAllowed,
+
+    #[error("Invalid caller")]
+    InvalidCaller,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use linera_sdk::{
+        base::{ApplicationId, BlockHeight, BytecodeId, ChainId, MessageId, Owner},
+        CalleeContext,
+    };
+
+    use super::{check_caller, known_author, ContractError, StateError};
+
+    fn application_id(index: u32) -> ApplicationId {
+        let message_id = MessageId {
+            chain_id: ChainId::root(0),
+            height: BlockHeight(0),
+            index,
+        };
+        ApplicationId {
+            bytecode_id: BytecodeId::new(message_id),
+            creation: message_id,
+        }
+    }
+
+    fn context(caller_id: Option<ApplicationId>) -> CalleeContext {
+        CalleeContext {
+            chain_id: ChainId::root(0),
+            authenticated_signer: None,
+            authenticated_caller_id: caller_id,
+        }
+    }
+
+    #[test]
+    fn check_caller_accepts_review_app() {
+        let review_app_id = application_id(1);
+        assert!(check_caller(Some(review_app_id), &context(Some(review_app_id))).is_ok());
+    }
+
+    #[test]
+    fn check_caller_rejects_other_callers() {
+        let review_app_id = application_id(1);
+        for (allowed_app_id, caller_id) in [
+            (Some(review_app_id), Some(application_id(2))),
+            (Some(review_app_id), None),
+            (None, Some(review_app_id)),
+        ] {
+            assert!(matches!(
+                check_caller(allowed_app_id, &context(caller_id)),
+                Err(ContractError::InvalidCaller)
+            ));
+        }
+    }
+
+    #[test]
+    fn known_author_is_none_for_unknown_content() {
+        let author = Owner::from_str(&"01".repeat(32)).unwrap();
+        assert_eq!(known_author(Ok(author)).unwrap(), Some(author));
+        assert_eq!(known_author(Err(StateError::InvalidContent)).unwrap(), None);
+        assert!(matches!(
+            known_author(Err(StateError::AlreadyExists)),
+            Err(ContractError::StateError(StateError::AlreadyExists))
+        ));
+    }
 }
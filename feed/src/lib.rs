@@ -1,8 +1,18 @@
-use std::collections::HashMap;
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
-use async_graphql::{Request, Response, SimpleObject};
+use async_graphql::{Enum, InputObject, Request, Response, SimpleObject};
+use cid::{
+    multihash::{Code, MultihashDigest},
+    Cid,
+};
 use linera_sdk::base::{Amount, ApplicationId, ContractAbi, Owner, ServiceAbi, Timestamp};
+use linera_views::{common::CustomSerialize, views::ViewError};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 pub struct FeedAbi;
 
@@ -23,10 +33,168 @@ impl ServiceAbi for FeedAbi {
     type QueryResponse = Response;
 }
 
+/// Abi of the calls feed makes to review, which is set by SetReviewApp since it depends on feed
+pub struct ReviewCallAbi;
+
+impl ContractAbi for ReviewCallAbi {
+    type Parameters = ();
+    type InitializationArgument = ();
+    type Operation = ();
+    type Message = ();
+    type ApplicationCall = ReviewCall;
+    type SessionCall = ();
+    type SessionState = ();
+    type Response = bool;
+}
+
+/// Must stay the leading variants of review::ApplicationCall so review decodes them as its own
+#[derive(Debug, Deserialize, Serialize)]
+pub enum ReviewCall {
+    /// Reported content is flagged, reviewers vote to take it down
+    OpenTakedown { cid: String, reasons: Vec<String> },
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct FeedParameters {
     pub credit_app_id: ApplicationId<credit::CreditAbi>,
     pub foundation_app_id: ApplicationId<foundation::FoundationAbi>,
+    pub market_app_id: ApplicationId<market::MarketAbi>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Eq, PartialEq, Hash, Enum)]
+pub enum ReactionKind {
+    Like,
+    Dislike,
+    Love,
+    Laugh,
+    Celebrate,
+    Insightful,
+    Sad,
+    Angry,
+}
+
+impl fmt::Display for ReactionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Actions of an owner which are rate limited by the feed
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Eq, PartialEq, Hash, Enum)]
+pub enum Action {
+    React,
+    Comment,
+    Publish,
+    Tip,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// At most max_actions actions are allowed within any window_ms milliseconds,
+/// zero max_actions means no limit
+#[derive(
+    Debug,
+    Deserialize,
+    Serialize,
+    Clone,
+    Copy,
+    Default,
+    SimpleObject,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+)]
+pub struct RateLimit {
+    pub window_ms: u64,
+    pub max_actions: u32,
+}
+
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Default, SimpleObject, Eq, Ord, PartialEq, PartialOrd,
+)]
+pub struct RateLimits {
+    pub react: RateLimit,
+    pub comment: RateLimit,
+    pub publish: RateLimit,
+    pub tip: RateLimit,
+}
+
+impl RateLimits {
+    pub fn limit(&self, action: Action) -> RateLimit {
+        match action {
+            Action::React => self.react,
+            Action::Comment => self.comment,
+            Action::Publish => self.publish,
+            Action::Tip => self.tip,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Eq, PartialEq, Enum)]
+pub enum BodyStorage {
+    /// Full body is stored on chain
+    Inline,
+    /// Only an excerpt is stored on chain, full body should be fetched from ipfs with the cid
+    External,
+}
+
+pub const MAX_AUTHORS: usize = 16;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, SimpleObject, InputObject, Eq, PartialEq)]
+#[graphql(input_name = "AuthorShareInput")]
+pub struct AuthorShare {
+    pub owner: Owner,
+    pub percent: u8,
+}
+
+/// Shares must be distinct, positive, include the author and sum up to 100 percent,
+/// empty shares means the author takes all
+pub fn validate_shares(author: Owner, shares: &[AuthorShare]) -> bool {
+    if shares.is_empty() {
+        return true;
+    }
+    let owners = shares
+        .iter()
+        .map(|share| share.owner)
+        .collect::<HashSet<_>>();
+    shares.len() <= MAX_AUTHORS
+        && owners.len() == shares.len()
+        && owners.contains(&author)
+        && shares.iter().all(|share| share.percent > 0)
+        && shares.iter().map(|share| share.percent as u32).sum::<u32>() == 100
+}
+
+/// Split amount by percent of shares, the last share takes the rounding remainder
+pub fn split_amount(amount: Amount, shares: &[AuthorShare]) -> Vec<(Owner, Amount)> {
+    let mut remain = amount;
+    let mut amounts = Vec::new();
+    for (index, share) in shares.iter().enumerate() {
+        let part = match index + 1 == shares.len() {
+            true => remain,
+            false => Amount::from_atto(
+                amount
+                    .saturating_mul(share.percent as u128)
+                    .saturating_div(Amount::from_atto(100)),
+            ),
+        };
+        remain = remain.saturating_sub(part);
+        amounts.push((share.owner, part));
+    }
+    amounts
+}
+
+/// Both parts are paid to unlock the content, either of them could be zero
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, SimpleObject, InputObject, Eq, PartialEq)]
+#[graphql(input_name = "PremiumPriceInput")]
+pub struct PremiumPrice {
+    /// Linera token paid from foundation balance, including platform fee
+    pub amount: Amount,
+    pub credits: Amount,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
@@ -35,49 +203,563 @@ pub struct Content {
     pub cid: String,
     pub comment_to_cid: Option<String>,
     pub author: Owner,
+    /// Co-authors with the author sharing rewards and tips, empty if author takes all
+    pub authors: Vec<AuthorShare>,
     pub title: String,
+    /// Full body or its excerpt, depending on body_storage
     pub content: String,
+    pub body_storage: BodyStorage,
+    /// Bytes of the full body
+    pub body_size: u64,
+    /// Lowercase topics of the content
+    pub tags: Vec<String>,
     pub likes: u64,
     pub dislikes: u64,
+    /// Like (true) or dislike (false) of owners, kept for like / dislike only clients
     pub accounts: HashMap<Owner, bool>,
+    pub reactions: HashMap<ReactionKind, u64>,
+    pub reactors: HashMap<Owner, ReactionKind>,
+    /// 0 for content, parent depth + 1 for comments
+    pub depth: u32,
+    /// Number of direct comments to the content
+    pub replies: u64,
+    /// Weighted sum of reactions, comments, recommends and tips
+    pub engagement: i64,
+    /// Ranking of hot timeline in millionths, higher is hotter
+    pub hot_score: i64,
+    /// Cid of the latest revision if the content is edited
+    pub revision_cid: Option<String>,
+    /// Readers must unlock premium content to get its decryption key
+    pub price: Option<PremiumPrice>,
     pub created_at: Timestamp,
+    pub edited_at: Option<Timestamp>,
+}
+
+impl Content {
+    pub fn shares(&self) -> Vec<AuthorShare> {
+        match self.authors.is_empty() {
+            true => vec![AuthorShare {
+                owner: self.author,
+                percent: 100,
+            }],
+            false => self.authors.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Tombstone {
+    pub cid: String,
+    pub reason: String,
+    /// Taken down by review, otherwise deleted by author
+    pub taken_down: bool,
+    pub removed_at: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Revision {
+    pub cid: String,
+    pub title: String,
+    pub content: String,
+    pub body_storage: BodyStorage,
+    pub created_at: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Eq, PartialEq, Enum)]
+pub enum CommentSort {
+    /// Oldest first
+    Time,
+    /// Most liked first
+    Likes,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Eq, PartialEq, Enum)]
+pub enum ContentSort {
+    Newest,
+    /// Engagement decayed by age
+    Hot,
+    /// Most net likes first
+    Top,
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+pub struct ContentConnection {
+    pub contents: Vec<Content>,
+    /// Pass as after to fetch the next page
+    pub end_cursor: Option<String>,
+    pub has_next_page: bool,
+}
+
+/// Position of a top level content in an ordered index, higher rank comes first
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct RankKey {
+    pub rank: i64,
+    pub cid: String,
+}
+
+impl RankKey {
+    pub fn new(content: &Content, sort: ContentSort) -> Self {
+        let rank = match sort {
+            ContentSort::Newest => content.created_at.micros() as i64,
+            ContentSort::Hot => content.hot_score,
+            ContentSort::Top => content.likes as i64 - content.dislikes as i64,
+        };
+        RankKey {
+            rank,
+            cid: content.cid.clone(),
+        }
+    }
+
+    pub fn cursor(&self) -> String {
+        format!("{}:{}", self.rank, self.cid)
+    }
+
+    pub fn from_cursor(cursor: &str) -> Option<Self> {
+        let (rank, cid) = cursor.split_once(':')?;
+        Some(RankKey {
+            rank: rank.parse().ok()?,
+            cid: cid.to_string(),
+        })
+    }
+}
+
+impl Ord for RankKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .rank
+            .cmp(&self.rank)
+            .then_with(|| self.cid.cmp(&other.cid))
+    }
+}
+
+impl PartialOrd for RankKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Flipped big endian rank followed by cid, so that keys are stored in the same order as Ord
+impl CustomSerialize for RankKey {
+    fn to_custom_bytes(&self) -> Result<Vec<u8>, ViewError> {
+        let mut bytes = (!((self.rank as u64) ^ (1 << 63))).to_be_bytes().to_vec();
+        bytes.extend_from_slice(self.cid.as_bytes());
+        Ok(bytes)
+    }
+
+    fn from_custom_bytes(bytes: &[u8]) -> Result<Self, ViewError> {
+        if bytes.len() < 8 {
+            return Err(ViewError::Serialization(bcs::Error::Eof));
+        }
+        let (rank, cid) = bytes.split_at(8);
+        let rank = !u64::from_be_bytes(rank.try_into().unwrap()) ^ (1 << 63);
+        Ok(RankKey {
+            rank: rank as i64,
+            cid: String::from_utf8(cid.to_vec())
+                .map_err(|_| ViewError::Serialization(bcs::Error::Utf8))?,
+        })
+    }
+}
+
+/// NFT in market used as avatar
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, SimpleObject, InputObject, Eq, PartialEq)]
+#[graphql(input_name = "AvatarInput")]
+pub struct Avatar {
+    pub collection_id: u64,
+    pub token_id: u16,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Profile {
+    pub owner: Owner,
+    pub display_name: String,
+    pub bio: String,
+    pub links: Vec<String>,
+    pub avatar: Option<Avatar>,
+    pub updated_at: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, SimpleObject, Eq, PartialEq)]
+pub struct AuthorStats {
+    /// Top level contents, comments are not counted
+    pub publications: u64,
+    pub likes_received: u64,
+    pub tips_received: Amount,
+    pub tip_credits_received: Amount,
+}
+
+#[derive(Debug, Clone, Default, SimpleObject)]
+pub struct FollowStats {
+    pub followers: u64,
+    pub followings: u64,
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+pub struct AuthorProfile {
+    pub owner: Owner,
+    pub profile: Option<Profile>,
+    pub stats: AuthorStats,
+    pub follow_stats: FollowStats,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct ReadingList {
+    pub id: u64,
+    pub owner: Owner,
+    pub name: String,
+    pub description: String,
+    /// Private lists are not listed to other users, though they are still readable on chain
+    pub public: bool,
+    /// Cids of contents in reading order
+    pub cids: Vec<String>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Series {
+    pub id: u64,
+    pub author: Owner,
+    pub title: String,
+    pub description: String,
+    /// Cids of author's contents in reading order
+    pub cids: Vec<String>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+pub struct SeriesItem {
+    pub cid: String,
+    /// Zero based position in the series
+    pub position: u32,
+    pub content: Option<Content>,
+    pub previous: Option<String>,
+    pub next: Option<String>,
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+pub struct SeriesDetail {
+    pub series: Series,
+    pub items: Vec<SeriesItem>,
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+pub struct CommentNode {
+    pub comment: Content,
+    pub replies: Vec<CommentNode>,
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+pub struct CommentConnection {
+    /// Number of direct comments to the content
+    pub total: u64,
+    pub comments: Vec<CommentNode>,
+    /// Pass as after to fetch the next page
+    pub end_cursor: Option<String>,
+    pub has_next_page: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Tip {
+    pub tipper: Owner,
+    /// Linera token tipped from foundation balance, including platform fee
+    pub amount: Amount,
+    pub credits: Amount,
+    pub created_at: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Unlock {
+    pub reader: Owner,
+    pub amount: Amount,
+    pub credits: Amount,
+    pub unlocked_at: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Report {
+    pub reporter: Owner,
+    pub reason: String,
+    pub created_at: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Eq, PartialEq, Enum)]
+pub enum ReportStatus {
+    Open,
+    /// Reports reached threshold, reviewers should vote to take it down or dismiss the reports
+    Flagged,
+    /// Content was taken down by review
+    Upheld,
+    /// Reviewers kept the content and reporters were penalized
+    Dismissed,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct ContentReports {
+    pub cid: String,
+    pub status: ReportStatus,
+    pub reports: Vec<Report>,
+    pub flagged_at: Option<Timestamp>,
+}
+
+/// Topics followed by a subscriber chain, which gets everything when both are empty
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Default, SimpleObject, InputObject, Eq, PartialEq,
+)]
+#[graphql(input_name = "TopicsInput")]
+pub struct Topics {
+    pub authors: Vec<Owner>,
+    /// Lowercase tags
+    pub tags: Vec<String>,
+}
+
+impl Topics {
+    pub fn is_empty(&self) -> bool {
+        self.authors.is_empty() && self.tags.is_empty()
+    }
+
+    pub fn matches(&self, author: Owner, tags: &[String]) -> bool {
+        self.authors.contains(&author) || tags.iter().any(|tag| self.tags.contains(tag))
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, SimpleObject, Eq, PartialEq)]
+pub struct ContentTips {
+    pub amount: Amount,
+    pub credits: Amount,
+    pub tips: Vec<Tip>,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct InitialState {
-    pub react_interval_ms: u64,
+    pub rate_limits: RateLimits,
+    /// Percent of tokens paid by tips and unlocks which is deposited to foundation
+    pub tip_fee_percent: u8,
+    /// Replies deeper than this are attached to the deepest allowed ancestor, 0 means no limit
+    pub max_comment_depth: u32,
+    /// Edits must be approved through review instead of being applied by author directly
+    pub edit_requires_review: bool,
+    /// Take back author reward from foundation when content is deleted or taken down
+    pub clawback_author_reward: bool,
+    /// Seconds of content age which weigh as much as 10x engagement in hot ranking
+    pub hot_gravity_secs: u64,
+    /// Check multihash of inline content against its cid, which must then use raw codec
+    pub verify_content_cid: bool,
+    /// Max bytes of content body, 0 means no limit
+    pub max_body_bytes: u64,
+    /// Keep only an excerpt of body on chain, the full body lives in ipfs under its cid
+    pub offload_body: bool,
+    /// Max bytes of the excerpt kept on chain when body is offloaded
+    pub excerpt_bytes: u64,
+    /// Content reported by this many owners is flagged for review, 0 means never
+    pub report_threshold: u32,
+    /// Credits taken from each reporter when reviewers dismiss the reports
+    pub bad_report_penalty: Amount,
+}
+
+/// Multicodec of raw binary, the only codec whose cid hashes the content bytes directly
+pub const RAW_CODEC: u64 = 0x55;
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum CidError {
+    #[error("Invalid cid")]
+    InvalidCid,
+
+    #[error("Codec {0:#x} is not verifiable, only raw codec is supported")]
+    UnsupportedCodec(u64),
+
+    #[error("Unsupported multihash code {0:#x}")]
+    UnsupportedHash(u64),
+
+    #[error("Content does not match cid")]
+    ContentMismatch,
+}
+
+pub fn parse_cid(cid: &str) -> Result<Cid, CidError> {
+    Cid::try_from(cid).map_err(|_| CidError::InvalidCid)
+}
+
+/// Parse cid and check that it's the raw cid of content
+pub fn verify_content_cid(cid: &str, content: &str) -> Result<Cid, CidError> {
+    let cid = parse_cid(cid)?;
+    if cid.codec() != RAW_CODEC {
+        return Err(CidError::UnsupportedCodec(cid.codec()));
+    }
+    let code = Code::try_from(cid.hash().code())
+        .map_err(|_| CidError::UnsupportedHash(cid.hash().code()))?;
+    if code.digest(content.as_bytes()) != *cid.hash() {
+        return Err(CidError::ContentMismatch);
+    }
+    Ok(cid)
+}
+
+pub const MAX_TOKEN_LEN: usize = 64;
+
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "been",
+    "but", "by", "can", "could", "do", "does", "for", "from", "had", "has", "have", "he", "her",
+    "his", "how", "i", "if", "in", "into", "is", "it", "its", "me", "my", "no", "not", "of", "on",
+    "or", "our", "she", "so", "than", "that", "the", "their", "them", "then", "there", "these",
+    "they", "this", "to", "up", "us", "was", "we", "were", "what", "when", "which", "who", "will",
+    "with", "would", "you", "your",
+];
+
+/// Lowercase words of text without stop words, repeated words are kept for term frequency
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && word.len() <= MAX_TOKEN_LEN)
+        .map(|word| word.to_lowercase())
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .collect()
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub enum Operation {
-    Like { cid: String },
-    Dislike { cid: String },
-    Tip { cid: String, amount: Amount },
-    RequestSubscribe,
+    Like {
+        cid: String,
+    },
+    Dislike {
+        cid: String,
+    },
+    Tip {
+        cid: String,
+        amount: Amount,
+        credits: Amount,
+    },
+    /// Allow the review application to call rate limited and reviewed actions
+    SetReviewApp {
+        application_id: ApplicationId,
+    },
+    React {
+        cid: String,
+        kind: ReactionKind,
+    },
+    Unreact {
+        cid: String,
+    },
+    Edit {
+        cid: String,
+        new_cid: String,
+        title: String,
+        content: String,
+    },
+    Delete {
+        cid: String,
+        reason: String,
+    },
+    Follow {
+        author: Owner,
+    },
+    Unfollow {
+        author: Owner,
+    },
+    UpdateProfile {
+        display_name: String,
+        bio: String,
+        links: Vec<String>,
+        avatar: Option<Avatar>,
+    },
+    Bookmark {
+        cid: String,
+    },
+    Unbookmark {
+        cid: String,
+    },
+    CreateReadingList {
+        name: String,
+        description: String,
+        public: bool,
+    },
+    DeleteReadingList {
+        list_id: u64,
+    },
+    AddToReadingList {
+        list_id: u64,
+        cid: String,
+    },
+    RemoveFromReadingList {
+        list_id: u64,
+        cid: String,
+    },
+    /// Move the content to position of the list, position past the end moves it to the end
+    MoveReadingListItem {
+        list_id: u64,
+        cid: String,
+        position: u32,
+    },
+    /// None makes the content free, readers who already unlocked it are kept
+    SetPremium {
+        cid: String,
+        price: Option<PremiumPrice>,
+    },
+    Unlock {
+        cid: String,
+    },
+    Report {
+        cid: String,
+        reason: String,
+    },
+    CreateSeries {
+        title: String,
+        description: String,
+    },
+    AppendToSeries {
+        series_id: u64,
+        cid: String,
+    },
+    RemoveFromSeries {
+        series_id: u64,
+        cid: String,
+    },
+    /// Move the content to position of the series, position past the end moves it to the end
+    MoveSeriesItem {
+        series_id: u64,
+        cid: String,
+        position: u32,
+    },
+    /// Fetch the page of contents published before cursor from creation chain
+    RequestBackfill {
+        cursor: u64,
+    },
+    RequestSubscribe {
+        topics: Topics,
+    },
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum Message {
-    Like {
+    React {
         cid: String,
+        kind: ReactionKind,
     },
-    Dislike {
+    Unreact {
         cid: String,
     },
+    SetReviewApp {
+        application_id: ApplicationId,
+    },
     Tip {
         cid: String,
         amount: Amount,
+        credits: Amount,
     },
     Publish {
         cid: String,
         title: String,
+        /// Full body, or its excerpt if the body is offloaded
         content: String,
+        body_storage: BodyStorage,
+        /// Bytes of the full body
+        body_size: u64,
         author: Owner,
+        tags: Vec<String>,
+        authors: Vec<AuthorShare>,
     },
     Recommend {
         cid: String,
         reason_cid: String,
         reason: String,
+        recommender: Owner,
     },
     Comment {
         cid: String,
@@ -85,7 +767,114 @@ pub enum Message {
         comment: String,
         commentor: Owner,
     },
-    RequestSubscribe,
+    Edit {
+        cid: String,
+        new_cid: String,
+        title: String,
+        content: String,
+    },
+    ReviewedEdit {
+        cid: String,
+        new_cid: String,
+        title: String,
+        content: String,
+        author: Owner,
+    },
+    Delete {
+        cid: String,
+        reason: String,
+    },
+    Takedown {
+        cid: String,
+        reason: String,
+    },
+    Follow {
+        author: Owner,
+    },
+    Unfollow {
+        author: Owner,
+    },
+    UpdateProfile {
+        display_name: String,
+        bio: String,
+        links: Vec<String>,
+        avatar: Option<Avatar>,
+    },
+    Bookmark {
+        cid: String,
+    },
+    Unbookmark {
+        cid: String,
+    },
+    CreateReadingList {
+        name: String,
+        description: String,
+        public: bool,
+    },
+    DeleteReadingList {
+        list_id: u64,
+    },
+    AddToReadingList {
+        list_id: u64,
+        cid: String,
+    },
+    RemoveFromReadingList {
+        list_id: u64,
+        cid: String,
+    },
+    /// Move the content to position of the list, position past the end moves it to the end
+    MoveReadingListItem {
+        list_id: u64,
+        cid: String,
+        position: u32,
+    },
+    /// None makes the content free, readers who already unlocked it are kept
+    SetPremium {
+        cid: String,
+        price: Option<PremiumPrice>,
+    },
+    Unlock {
+        cid: String,
+    },
+    Report {
+        cid: String,
+        reason: String,
+    },
+    DismissReports {
+        cid: String,
+    },
+    CreateSeries {
+        title: String,
+        description: String,
+    },
+    AppendToSeries {
+        series_id: u64,
+        cid: String,
+    },
+    RemoveFromSeries {
+        series_id: u64,
+        cid: String,
+    },
+    /// Move the content to position of the series, position past the end moves it to the end
+    MoveSeriesItem {
+        series_id: u64,
+        cid: String,
+        position: u32,
+    },
+    RequestBackfill {
+        cursor: u64,
+    },
+    InitialState {
+        state: InitialState,
+    },
+    /// Newest first contents for a subscriber, cursor of the older page is none when finished
+    Backfill {
+        contents: Vec<Content>,
+        cursor: Option<u64>,
+    },
+    RequestSubscribe {
+        topics: Topics,
+    },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -95,11 +884,14 @@ pub enum ApplicationCall {
         title: String,
         content: String,
         author: Owner,
+        tags: Vec<String>,
+        authors: Vec<AuthorShare>,
     },
     Recommend {
         cid: String,
         reason_cid: String,
         reason: String,
+        recommender: Owner,
     },
     Comment {
         cid: String,
@@ -110,4 +902,203 @@ pub enum ApplicationCall {
     ContentAuthor {
         cid: String,
     },
+    /// Apply an edit approved by review
+    Edit {
+        cid: String,
+        new_cid: String,
+        title: String,
+        content: String,
+        author: Owner,
+    },
+    /// Fail if the content has none of the tags
+    CheckTags {
+        cid: String,
+        tags: Vec<String>,
+    },
+    /// Remove content after reviewers voted to take it down
+    Takedown {
+        cid: String,
+        reason: String,
+    },
+    /// Record an action of owner, fail if it exceeds the rate limit
+    RateLimit {
+        owner: Owner,
+        action: Action,
+    },
+    /// Close reports after reviewers voted to keep the content
+    DismissReports {
+        cid: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use cid::{
+        multihash::{Code, Multihash, MultihashDigest},
+        Cid,
+    };
+    use linera_sdk::base::{Amount, Owner};
+
+    use super::{
+        split_amount, tokenize, validate_shares, verify_content_cid, AuthorShare, CidError,
+        MAX_TOKEN_LEN, RAW_CODEC,
+    };
+
+    fn owner(byte: u8) -> Owner {
+        Owner::from_str(&format!("{:02x}", byte).repeat(32)).unwrap()
+    }
+
+    fn shares(percents: &[(u8, u8)]) -> Vec<AuthorShare> {
+        percents
+            .iter()
+            .map(|(byte, percent)| AuthorShare {
+                owner: owner(*byte),
+                percent: *percent,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn validate_shares_accepts_empty_and_full_shares() {
+        assert!(validate_shares(owner(1), &[]));
+        assert!(validate_shares(owner(1), &shares(&[(1, 60), (2, 40)])));
+        assert!(validate_shares(owner(1), &shares(&[(1, 100)])));
+    }
+
+    #[test]
+    fn validate_shares_rejects_percents_not_summing_to_100() {
+        assert!(!validate_shares(owner(1), &shares(&[(1, 60), (2, 30)])));
+        assert!(!validate_shares(owner(1), &shares(&[(1, 60), (2, 50)])));
+        assert!(!validate_shares(owner(1), &shares(&[(1, 200), (2, 100)])));
+        assert!(!validate_shares(owner(2), &shares(&[(1, 100), (2, 0)])));
+    }
+
+    #[test]
+    fn validate_shares_rejects_invalid_owners() {
+        assert!(!validate_shares(owner(3), &shares(&[(1, 60), (2, 40)])));
+        assert!(!validate_shares(owner(1), &shares(&[(1, 60), (1, 40)])));
+        let too_many = (0..=super::MAX_AUTHORS as u8)
+            .map(|byte| (byte, 1))
+            .collect::<Vec<_>>();
+        assert!(!validate_shares(owner(0), &shares(&too_many)));
+    }
+
+    #[test]
+    fn split_amount_gives_remainder_to_last_share() {
+        let amounts = split_amount(Amount::from_atto(10), &shares(&[(1, 33), (2, 33), (3, 34)]));
+        assert_eq!(
+            amounts,
+            vec![
+                (owner(1), Amount::from_atto(3)),
+                (owner(2), Amount::from_atto(3)),
+                (owner(3), Amount::from_atto(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn split_amount_distributes_whole_amount_when_percents_not_summing_to_100() {
+        let amounts = split_amount(Amount::from_tokens(100), &shares(&[(1, 50), (2, 30)]));
+        assert_eq!(
+            amounts,
+            vec![
+                (owner(1), Amount::from_tokens(50)),
+                (owner(2), Amount::from_tokens(50)),
+            ]
+        );
+        assert!(split_amount(Amount::from_tokens(100), &[]).is_empty());
+    }
+
+    #[test]
+    fn tokenize_splits_on_non_alphanumeric_and_lowercases() {
+        assert_eq!(
+            tokenize("Hello, World! Rust-lang 2023"),
+            vec!["hello", "world", "rust", "lang", "2023"]
+        );
+        assert_eq!(tokenize("fox Fox FOX"), vec!["fox", "fox", "fox"]);
+        assert!(tokenize("  ,.;!  ").is_empty());
+    }
+
+    #[test]
+    fn tokenize_skips_stop_words() {
+        assert_eq!(
+            tokenize("The quick fox is in THE box"),
+            vec!["quick", "fox", "box"]
+        );
+        assert!(tokenize("a an and the of").is_empty());
+    }
+
+    #[test]
+    fn tokenize_keeps_unicode_words() {
+        assert_eq!(
+            tokenize("Café ÉCOLE, 日本語。Привет"),
+            vec!["café", "école", "日本語", "привет"]
+        );
+    }
+
+    #[test]
+    fn tokenize_drops_words_longer_than_max_bytes() {
+        let longest = "a".repeat(MAX_TOKEN_LEN);
+        let text = format!("{} {}b", longest, longest);
+        assert_eq!(tokenize(&text), vec![longest]);
+        // Length is counted in bytes, each of these chars takes 3 bytes
+        let wide = "語".repeat(MAX_TOKEN_LEN / 3);
+        let text = format!("{} {}語", wide, wide);
+        assert_eq!(tokenize(&text), vec![wide]);
+    }
+
+    const DAG_PB_CODEC: u64 = 0x70;
+
+    fn raw_cid(content: &str) -> String {
+        Cid::new_v1(RAW_CODEC, Code::Sha2_256.digest(content.as_bytes())).to_string()
+    }
+
+    #[test]
+    fn verify_content_cid_accepts_raw_cid_of_content() {
+        let cid = raw_cid("hello linera");
+        assert_eq!(
+            verify_content_cid(&cid, "hello linera")
+                .unwrap()
+                .to_string(),
+            cid
+        );
+        let cid = Cid::new_v1(RAW_CODEC, Code::Blake2b256.digest(b"hello linera")).to_string();
+        assert!(verify_content_cid(&cid, "hello linera").is_ok());
+    }
+
+    #[test]
+    fn verify_content_cid_rejects_mismatched_content() {
+        assert_eq!(
+            verify_content_cid(&raw_cid("hello linera"), "hello linera!"),
+            Err(CidError::ContentMismatch)
+        );
+    }
+
+    #[test]
+    fn verify_content_cid_rejects_unsupported_codec_and_hash() {
+        let cid = Cid::new_v1(DAG_PB_CODEC, Code::Sha2_256.digest(b"hello linera")).to_string();
+        assert_eq!(
+            verify_content_cid(&cid, "hello linera"),
+            Err(CidError::UnsupportedCodec(DAG_PB_CODEC))
+        );
+        let cid = Cid::new_v1(RAW_CODEC, Multihash::wrap(0x1234, &[0; 32]).unwrap()).to_string();
+        assert_eq!(
+            verify_content_cid(&cid, "hello linera"),
+            Err(CidError::UnsupportedHash(0x1234))
+        );
+    }
+
+    #[test]
+    fn verify_content_cid_rejects_invalid_cid() {
+        assert_eq!(
+            verify_content_cid("not a cid", "hello linera"),
+            Err(CidError::InvalidCid)
+        );
+        assert_eq!(
+            verify_content_cid("", "hello linera"),
+            Err(CidError::InvalidCid)
+        );
+    }
 }
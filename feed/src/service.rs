@@ -3,11 +3,16 @@
 mod state;
 
 use self::state::Feed;
-use async_graphql::{EmptySubscription, Object, Request, Response, Schema};
+use async_graphql::{EmptySubscription, MergedObject, Object, Request, Response, Schema};
 use async_trait::async_trait;
-use feed::Operation;
+use feed::{
+    Action, AuthorProfile, Avatar, CommentConnection, CommentSort, Content, ContentConnection,
+    ContentReports, ContentSort, FollowStats, Operation, PremiumPrice, ReactionKind, ReadingList,
+    ReportStatus, Revision, Series, SeriesDetail, Topics,
+};
 use linera_sdk::{
-    base::{Amount, WithServiceAbi},
+    base::{Amount, ApplicationId, Owner, Timestamp, WithServiceAbi},
+    service::system_api,
     QueryContext, Service, ViewStateStorage,
 };
 use std::sync::Arc;
@@ -30,13 +35,241 @@ impl Service for Feed {
         request: Request,
     ) -> Result<Response, Self::Error> {
         // TODO: we need to filter content according to requester and review state here
-        let schema: Schema<Arc<Feed>, MutationRoot, EmptySubscription> =
-            Schema::build(self.clone(), MutationRoot {}, EmptySubscription).finish();
+        let schema = Schema::build(
+            QueryRoot(
+                self.clone(),
+                Query {
+                    state: self.clone(),
+                },
+            ),
+            MutationRoot {},
+            EmptySubscription,
+        )
+        .finish();
         let response = schema.execute(request).await;
         Ok(response)
     }
 }
 
+struct Query {
+    state: Arc<Feed>,
+}
+
+#[Object]
+impl Query {
+    /// Milliseconds to wait before owner could perform the action again
+    async fn cooldown(&self, owner: Owner, action: Action) -> Result<u64, async_graphql::Error> {
+        Ok(self
+            .state
+            .cooldown_ms(owner, action, system_api::current_system_time())
+            .await?)
+    }
+
+    /// Comment tree of the content, paginated by its direct comments
+    async fn comment_tree(
+        &self,
+        cid: String,
+        sort: Option<CommentSort>,
+        first: Option<usize>,
+        after: Option<String>,
+    ) -> Result<CommentConnection, async_graphql::Error> {
+        Ok(self
+            .state
+            .comment_page(
+                cid,
+                sort.unwrap_or(CommentSort::Time),
+                first.unwrap_or(20),
+                after,
+            )
+            .await?)
+    }
+
+    async fn timeline(
+        &self,
+        first: Option<usize>,
+        after: Option<String>,
+        sort: Option<ContentSort>,
+    ) -> Result<ContentConnection, async_graphql::Error> {
+        Ok(self
+            .state
+            .timeline_page(
+                sort.unwrap_or(ContentSort::Newest),
+                first.unwrap_or(20),
+                after,
+            )
+            .await?)
+    }
+
+    async fn by_author(
+        &self,
+        owner: Owner,
+        first: Option<usize>,
+        after: Option<String>,
+        sort: Option<ContentSort>,
+    ) -> Result<ContentConnection, async_graphql::Error> {
+        Ok(self
+            .state
+            .author_page(
+                owner,
+                sort.unwrap_or(ContentSort::Newest),
+                first.unwrap_or(20),
+                after,
+            )
+            .await?)
+    }
+
+    async fn by_tag(
+        &self,
+        tag: String,
+        first: Option<usize>,
+        after: Option<String>,
+        sort: Option<ContentSort>,
+    ) -> Result<ContentConnection, async_graphql::Error> {
+        Ok(self
+            .state
+            .tag_page(
+                tag,
+                sort.unwrap_or(ContentSort::Newest),
+                first.unwrap_or(20),
+                after,
+            )
+            .await?)
+    }
+
+    /// Publications matching words of query ranked by relevance
+    async fn search(
+        &self,
+        query: String,
+        first: Option<usize>,
+        after: Option<String>,
+        author: Option<Owner>,
+        tag: Option<String>,
+        since: Option<Timestamp>,
+        until: Option<Timestamp>,
+    ) -> Result<ContentConnection, async_graphql::Error> {
+        Ok(self
+            .state
+            .search_page(query, author, tag, since, until, first.unwrap_or(20), after)
+            .await?)
+    }
+
+    /// Newest publications of authors followed by owner
+    async fn following_timeline(
+        &self,
+        owner: Owner,
+        first: Option<usize>,
+        after: Option<String>,
+    ) -> Result<ContentConnection, async_graphql::Error> {
+        Ok(self
+            .state
+            .following_page(owner, first.unwrap_or(20), after)
+            .await?)
+    }
+
+    async fn profile(&self, owner: Owner) -> Result<AuthorProfile, async_graphql::Error> {
+        Ok(self.state.author_profile(owner).await?)
+    }
+
+    async fn follow_stats(&self, owner: Owner) -> Result<FollowStats, async_graphql::Error> {
+        Ok(self.state.follow_stats(owner).await?)
+    }
+
+    /// Bookmarked contents of owner, most recently bookmarked first
+    async fn bookmarked_contents(
+        &self,
+        owner: Owner,
+    ) -> Result<Vec<Content>, async_graphql::Error> {
+        Ok(self.state.bookmarked_contents(owner).await?)
+    }
+
+    async fn reading_lists_of(
+        &self,
+        owner: Owner,
+        include_private: Option<bool>,
+    ) -> Result<Vec<ReadingList>, async_graphql::Error> {
+        Ok(self
+            .state
+            .reading_lists_of(owner, include_private.unwrap_or(false))
+            .await?)
+    }
+
+    /// Public reading lists of all users, newest first
+    async fn public_reading_lists(
+        &self,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> Result<Vec<ReadingList>, async_graphql::Error> {
+        Ok(self
+            .state
+            .public_reading_lists(offset.unwrap_or(0), limit.unwrap_or(20))
+            .await?)
+    }
+
+    async fn reading_list_contents(
+        &self,
+        list_id: u64,
+    ) -> Result<Vec<Content>, async_graphql::Error> {
+        Ok(self.state.reading_list_contents(list_id).await?)
+    }
+
+    /// Whether reader could access the content, clients release decryption keys of premium content by it
+    async fn unlocked(&self, cid: String, reader: Owner) -> Result<bool, async_graphql::Error> {
+        Ok(self.state.unlocked(cid, reader).await?)
+    }
+
+    /// Reports for reviewers, most reported first
+    async fn reported_contents(
+        &self,
+        status: Option<ReportStatus>,
+    ) -> Result<Vec<ContentReports>, async_graphql::Error> {
+        Ok(self.state.reported_contents(status).await?)
+    }
+
+    async fn series_of(&self, author: Owner) -> Result<Vec<Series>, async_graphql::Error> {
+        Ok(self.state.series_of(author).await?)
+    }
+
+    /// Series with previous and next cids of each member content
+    async fn series_detail(&self, series_id: u64) -> Result<SeriesDetail, async_graphql::Error> {
+        Ok(self.state.series_detail(series_id).await?)
+    }
+
+    /// Series of the content with only the item of the content, none if it's not in a series
+    async fn series_navigation(
+        &self,
+        cid: String,
+    ) -> Result<Option<SeriesDetail>, async_graphql::Error> {
+        Ok(self.state.series_navigation(cid).await?)
+    }
+
+    /// Direct comments of the content, use commentTree for replies
+    async fn comments(
+        &self,
+        cid: String,
+        first: Option<usize>,
+        after: Option<String>,
+        sort: Option<ContentSort>,
+    ) -> Result<ContentConnection, async_graphql::Error> {
+        Ok(self
+            .state
+            .comments_page(
+                cid,
+                sort.unwrap_or(ContentSort::Newest),
+                first.unwrap_or(20),
+                after,
+            )
+            .await?)
+    }
+
+    /// Revisions of the content from the original one, empty if it's never edited
+    async fn revision_history(&self, cid: String) -> Result<Vec<Revision>, async_graphql::Error> {
+        Ok(self.state.revision_history(cid).await?)
+    }
+}
+
+#[derive(MergedObject)]
+struct QueryRoot(Arc<Feed>, Query);
+
 struct MutationRoot;
 
 #[Object]
@@ -51,13 +284,167 @@ impl MutationRoot {
         bcs::to_bytes(&Operation::Dislike { cid: ccid }).unwrap()
     }
 
-    async fn tip(&self, ccid: String, amount: Amount) -> Vec<u8> {
+    async fn react(&self, ccid: String, kind: ReactionKind) -> Vec<u8> {
+        cid::Cid::try_from(ccid.clone()).expect("Invalid content cid");
+        bcs::to_bytes(&Operation::React { cid: ccid, kind }).unwrap()
+    }
+
+    async fn unreact(&self, ccid: String) -> Vec<u8> {
         cid::Cid::try_from(ccid.clone()).expect("Invalid content cid");
-        bcs::to_bytes(&Operation::Tip { cid: ccid, amount }).unwrap()
+        bcs::to_bytes(&Operation::Unreact { cid: ccid }).unwrap()
+    }
+
+    async fn set_review_app(&self, application_id: ApplicationId) -> Vec<u8> {
+        bcs::to_bytes(&Operation::SetReviewApp { application_id }).unwrap()
+    }
+
+    async fn tip(&self, ccid: String, amount: Amount, credits: Amount) -> Vec<u8> {
+        cid::Cid::try_from(ccid.clone()).expect("Invalid content cid");
+        bcs::to_bytes(&Operation::Tip {
+            cid: ccid,
+            amount,
+            credits,
+        })
+        .unwrap()
+    }
+
+    async fn edit(&self, ccid: String, new_cid: String, title: String, content: String) -> Vec<u8> {
+        cid::Cid::try_from(ccid.clone()).expect("Invalid content cid");
+        cid::Cid::try_from(new_cid.clone()).expect("Invalid content cid");
+        bcs::to_bytes(&Operation::Edit {
+            cid: ccid,
+            new_cid,
+            title,
+            content,
+        })
+        .unwrap()
+    }
+
+    async fn delete(&self, ccid: String, reason: String) -> Vec<u8> {
+        bcs::to_bytes(&Operation::Delete { cid: ccid, reason }).unwrap()
+    }
+
+    async fn follow(&self, author: Owner) -> Vec<u8> {
+        bcs::to_bytes(&Operation::Follow { author }).unwrap()
+    }
+
+    async fn unfollow(&self, author: Owner) -> Vec<u8> {
+        bcs::to_bytes(&Operation::Unfollow { author }).unwrap()
+    }
+
+    async fn update_profile(
+        &self,
+        display_name: String,
+        bio: String,
+        links: Vec<String>,
+        avatar: Option<Avatar>,
+    ) -> Vec<u8> {
+        bcs::to_bytes(&Operation::UpdateProfile {
+            display_name,
+            bio,
+            links,
+            avatar,
+        })
+        .unwrap()
+    }
+
+    async fn bookmark(&self, ccid: String) -> Vec<u8> {
+        bcs::to_bytes(&Operation::Bookmark { cid: ccid }).unwrap()
+    }
+
+    async fn unbookmark(&self, ccid: String) -> Vec<u8> {
+        bcs::to_bytes(&Operation::Unbookmark { cid: ccid }).unwrap()
+    }
+
+    async fn create_reading_list(
+        &self,
+        name: String,
+        description: String,
+        public: bool,
+    ) -> Vec<u8> {
+        bcs::to_bytes(&Operation::CreateReadingList {
+            name,
+            description,
+            public,
+        })
+        .unwrap()
+    }
+
+    async fn delete_reading_list(&self, list_id: u64) -> Vec<u8> {
+        bcs::to_bytes(&Operation::DeleteReadingList { list_id }).unwrap()
+    }
+
+    async fn add_to_reading_list(&self, list_id: u64, ccid: String) -> Vec<u8> {
+        bcs::to_bytes(&Operation::AddToReadingList { list_id, cid: ccid }).unwrap()
+    }
+
+    async fn remove_from_reading_list(&self, list_id: u64, ccid: String) -> Vec<u8> {
+        bcs::to_bytes(&Operation::RemoveFromReadingList { list_id, cid: ccid }).unwrap()
+    }
+
+    async fn move_reading_list_item(&self, list_id: u64, ccid: String, position: u32) -> Vec<u8> {
+        bcs::to_bytes(&Operation::MoveReadingListItem {
+            list_id,
+            cid: ccid,
+            position,
+        })
+        .unwrap()
+    }
+
+    async fn set_premium(&self, ccid: String, price: Option<PremiumPrice>) -> Vec<u8> {
+        cid::Cid::try_from(ccid.clone()).expect("Invalid content cid");
+        bcs::to_bytes(&Operation::SetPremium { cid: ccid, price }).unwrap()
+    }
+
+    async fn unlock(&self, ccid: String) -> Vec<u8> {
+        cid::Cid::try_from(ccid.clone()).expect("Invalid content cid");
+        bcs::to_bytes(&Operation::Unlock { cid: ccid }).unwrap()
+    }
+
+    async fn report(&self, ccid: String, reason: String) -> Vec<u8> {
+        cid::Cid::try_from(ccid.clone()).expect("Invalid content cid");
+        bcs::to_bytes(&Operation::Report { cid: ccid, reason }).unwrap()
+    }
+
+    async fn create_series(&self, title: String, description: String) -> Vec<u8> {
+        bcs::to_bytes(&Operation::CreateSeries { title, description }).unwrap()
+    }
+
+    async fn append_to_series(&self, series_id: u64, ccid: String) -> Vec<u8> {
+        cid::Cid::try_from(ccid.clone()).expect("Invalid content cid");
+        bcs::to_bytes(&Operation::AppendToSeries {
+            series_id,
+            cid: ccid,
+        })
+        .unwrap()
+    }
+
+    async fn remove_from_series(&self, series_id: u64, ccid: String) -> Vec<u8> {
+        bcs::to_bytes(&Operation::RemoveFromSeries {
+            series_id,
+            cid: ccid,
+        })
+        .unwrap()
+    }
+
+    async fn move_series_item(&self, series_id: u64, ccid: String, position: u32) -> Vec<u8> {
+        bcs::to_bytes(&Operation::MoveSeriesItem {
+            series_id,
+            cid: ccid,
+            position,
+        })
+        .unwrap()
+    }
+
+    async fn request_backfill(&self, cursor: u64) -> Vec<u8> {
+        bcs::to_bytes(&Operation::RequestBackfill { cursor }).unwrap()
     }
 
-    async fn request_subscribe(&self) -> Vec<u8> {
-        bcs::to_bytes(&Operation::RequestSubscribe).unwrap()
+    async fn request_subscribe(&self, topics: Option<Topics>) -> Vec<u8> {
+        bcs::to_bytes(&Operation::RequestSubscribe {
+            topics: topics.unwrap_or_default(),
+        })
+        .unwrap()
     }
 }
 
@@ -1,12 +1,42 @@
-use feed::{Content, InitialState};
+use std::collections::{HashMap, HashSet};
+
+use feed::{
+    parse_cid, split_amount, tokenize, validate_shares, verify_content_cid, Action, AuthorProfile,
+    AuthorShare, AuthorStats, Avatar, BodyStorage, CidError, CommentConnection, CommentNode,
+    CommentSort, Content, ContentConnection, ContentReports, ContentSort, ContentTips, FollowStats,
+    InitialState, PremiumPrice, Profile, RankKey, RateLimit, RateLimits, ReactionKind, ReadingList,
+    Report, ReportStatus, Revision, Series, SeriesDetail, SeriesItem, Tip, Tombstone, Topics,
+    Unlock,
+};
 use linera_sdk::{
-    base::{Owner, Timestamp},
+    base::{Amount, ApplicationId, ChainId, Owner, Timestamp},
     contract::system_api::current_system_time,
-    views::{MapView, RegisterView, ViewStorageContext},
+    views::{CollectionView, CustomSetView, LogView, MapView, RegisterView, ViewStorageContext},
 };
-use linera_views::views::{GraphQLView, RootView};
+use linera_views::views::{GraphQLView, RootView, View};
 use thiserror::Error;
 
+const COMMENT_ENGAGEMENT: i64 = 2;
+const RECOMMEND_ENGAGEMENT: i64 = 3;
+const TIP_ENGAGEMENT: i64 = 5;
+
+const MAX_DISPLAY_NAME_LEN: usize = 64;
+const MAX_BIO_LEN: usize = 1024;
+const MAX_LINKS: usize = 8;
+
+const MAX_READING_LIST_NAME_LEN: usize = 128;
+const MAX_READING_LIST_DESCRIPTION_LEN: usize = 1024;
+
+const MAX_SERIES_TITLE_LEN: usize = 256;
+const MAX_SERIES_DESCRIPTION_LEN: usize = 4096;
+
+const MAX_TOPICS: usize = 64;
+
+const MAX_BACKFILL_SCAN: usize = 256;
+
+const TITLE_TERM_WEIGHT: u32 = 3;
+const MAX_QUERY_TERMS: usize = 16;
+
 #[derive(RootView, GraphQLView)]
 #[view(context = "ViewStorageContext")]
 pub struct Feed {
@@ -14,30 +44,211 @@ pub struct Feed {
     pub content_recommends: MapView<String, Vec<String>>,
     pub content_comments: MapView<String, Vec<String>>,
     pub publishes: MapView<Owner, Vec<String>>,
-    pub react_interval_ms: RegisterView<u64>,
-    pub react_accounts: MapView<Owner, Timestamp>,
+    /// Top level contents of each author in each sort order
+    pub author_contents: CollectionView<Owner, ContentIndex>,
+    pub rate_limits: RegisterView<RateLimits>,
+    /// Timestamps of recent actions of owners, pruned to the rate limit window
+    pub action_windows: MapView<Owner, HashMap<Action, Vec<Timestamp>>>,
+    /// Review application allowed to call rate limited and reviewed actions
+    pub review_app_id: RegisterView<Option<ApplicationId>>,
     pub collection_recommends: MapView<u64, Vec<String>>,
     pub collection_comments: MapView<u64, Vec<String>>,
+    pub tip_fee_percent: RegisterView<u8>,
+    pub content_tips: MapView<String, ContentTips>,
+    /// Owners who already got rewards of reacting to the content
+    pub reaction_rewards: MapView<String, HashSet<Owner>>,
+    pub max_comment_depth: RegisterView<u32>,
+    pub edit_requires_review: RegisterView<bool>,
+    /// Revision history of edited contents, keyed by the original cid
+    pub revisions: MapView<String, Vec<Revision>>,
+    /// Original cid of each revision cid
+    pub revision_of: MapView<String, String>,
+    pub clawback_author_reward: RegisterView<bool>,
+    /// Removed contents are moved out of contents so they're hidden from queries
+    pub removed_contents: MapView<String, Content>,
+    pub tombstones: MapView<String, Tombstone>,
+    /// Cids of top level contents in publish order
+    pub timeline_index: LogView<String>,
+    /// Cids of backfilled top level contents from newest to oldest, all older than timeline index
+    pub backfill_index: LogView<String>,
+    /// Top level contents ordered by hot score
+    pub hot_index: CustomSetView<RankKey>,
+    /// Top level contents ordered by net likes
+    pub top_index: CustomSetView<RankKey>,
+    pub hot_gravity_secs: RegisterView<u64>,
+    pub verify_content_cid: RegisterView<bool>,
+    pub max_body_bytes: RegisterView<u64>,
+    pub offload_body: RegisterView<bool>,
+    pub excerpt_bytes: RegisterView<u64>,
+    pub report_threshold: RegisterView<u32>,
+    pub bad_report_penalty: RegisterView<Amount>,
+    /// Top level contents of each tag in each sort order
+    pub tag_contents: CollectionView<String, ContentIndex>,
+    pub followers: MapView<Owner, HashSet<Owner>>,
+    pub followings: MapView<Owner, HashSet<Owner>>,
+    pub profiles: MapView<Owner, Profile>,
+    pub author_stats: MapView<Owner, AuthorStats>,
+    pub bookmarks: MapView<Owner, Vec<String>>,
+    pub reading_list_id: RegisterView<u64>,
+    pub reading_lists: MapView<u64, ReadingList>,
+    pub owner_reading_list_ids: MapView<Owner, Vec<u64>>,
+    pub unlocks: MapView<String, Vec<Unlock>>,
+    pub reports: MapView<String, ContentReports>,
+    pub series_id: RegisterView<u64>,
+    pub series: MapView<u64, Series>,
+    pub author_series: MapView<Owner, Vec<u64>>,
+    pub series_of_content: MapView<String, u64>,
+    /// Subscriber chains replicating only content of their topics, kept on creation chain
+    pub subscriber_topics: MapView<ChainId, Topics>,
+    /// Reverse indices of subscriber topics to route a message without scanning all subscribers
+    pub author_subscribers: MapView<Owner, HashSet<ChainId>>,
+    pub tag_subscribers: MapView<String, HashSet<ChainId>>,
+    /// Timeline position before which contents are not backfilled yet, kept on subscriber chains
+    pub backfill_cursor: RegisterView<Option<u64>>,
+    /// Term to weighted term frequency in title and body of each publication
+    pub search_index: MapView<String, HashMap<String, u32>>,
+}
+
+/// Ordered indices of top level contents in a scope, keys are visited from the highest rank
+#[derive(View, GraphQLView)]
+#[view(context = "ViewStorageContext")]
+pub struct ContentIndex {
+    pub newest: CustomSetView<RankKey>,
+    pub hot: CustomSetView<RankKey>,
+    pub top: CustomSetView<RankKey>,
+}
+
+impl ContentIndex {
+    fn ranks(&self, sort: ContentSort) -> &CustomSetView<RankKey> {
+        match sort {
+            ContentSort::Newest => &self.newest,
+            ContentSort::Hot => &self.hot,
+            ContentSort::Top => &self.top,
+        }
+    }
+
+    fn insert(&mut self, content: &Content) -> Result<(), StateError> {
+        self.newest
+            .insert(&RankKey::new(content, ContentSort::Newest))?;
+        self.hot.insert(&RankKey::new(content, ContentSort::Hot))?;
+        self.top.insert(&RankKey::new(content, ContentSort::Top))?;
+        Ok(())
+    }
+
+    fn remove(&mut self, content: &Content) -> Result<(), StateError> {
+        self.newest
+            .remove(&RankKey::new(content, ContentSort::Newest))?;
+        self.hot.remove(&RankKey::new(content, ContentSort::Hot))?;
+        self.top.remove(&RankKey::new(content, ContentSort::Top))?;
+        Ok(())
+    }
 }
 
 #[allow(dead_code)]
 impl Feed {
-    pub(crate) async fn initialize_feed(&mut self, state: InitialState) {
-        self.react_interval_ms.set(state.react_interval_ms);
+    pub(crate) async fn initialize_feed(&mut self, state: InitialState) -> Result<(), StateError> {
+        if state.tip_fee_percent > 100 {
+            return Err(StateError::InvalidPercent);
+        }
+        self.rate_limits.set(state.rate_limits);
+        self.tip_fee_percent.set(state.tip_fee_percent);
+        self.max_comment_depth.set(state.max_comment_depth);
+        self.edit_requires_review.set(state.edit_requires_review);
+        self.clawback_author_reward
+            .set(state.clawback_author_reward);
+        self.hot_gravity_secs.set(state.hot_gravity_secs);
+        self.verify_content_cid.set(state.verify_content_cid);
+        self.max_body_bytes.set(state.max_body_bytes);
+        self.offload_body.set(state.offload_body);
+        self.excerpt_bytes.set(state.excerpt_bytes);
+        self.report_threshold.set(state.report_threshold);
+        self.bad_report_penalty.set(state.bad_report_penalty);
+        Ok(())
+    }
+
+    fn check_content_cid(&self, cid: &str, content: &str) -> Result<(), StateError> {
+        match *self.verify_content_cid.get() {
+            true => verify_content_cid(cid, content)?,
+            false => parse_cid(cid)?,
+        };
+        Ok(())
+    }
+
+    // Returns what to store on chain for the body, which is an excerpt if the body is offloaded
+    fn store_body(&self, body: String) -> Result<(String, BodyStorage), StateError> {
+        let size = body.len() as u64;
+        let max_size = *self.max_body_bytes.get();
+        if max_size > 0 && size > max_size {
+            return Err(StateError::BodyTooLarge(size, max_size));
+        }
+        let excerpt_bytes = *self.excerpt_bytes.get() as usize;
+        if !*self.offload_body.get() || body.len() <= excerpt_bytes {
+            return Ok((body, BodyStorage::Inline));
+        }
+        let mut end = excerpt_bytes;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        Ok((body[..end].to_string(), BodyStorage::External))
+    }
+
+    // Returns the stored body of the content with its storage and full size
+    pub(crate) async fn stored_body(
+        &self,
+        cid: String,
+    ) -> Result<(String, BodyStorage, u64), StateError> {
+        match self.contents.get(&cid).await? {
+            Some(content) => Ok((content.content, content.body_storage, content.body_size)),
+            None => Err(StateError::InvalidContent),
+        }
     }
 
     pub(crate) async fn create_content(
         &mut self,
-        content: Content,
+        mut content: Content,
         owner: Owner,
     ) -> Result<(), StateError> {
+        match content.body_storage {
+            // Offloaded body was checked on creation chain, subscribers only get its excerpt
+            BodyStorage::External => {
+                parse_cid(&content.cid)?;
+            }
+            BodyStorage::Inline => {
+                self.check_content_cid(&content.cid, &content.content)?;
+                content.body_size = content.content.len() as u64;
+                (content.content, content.body_storage) = self.store_body(content.content)?;
+            }
+        }
+        if !validate_shares(content.author, &content.authors) {
+            return Err(StateError::InvalidShares);
+        }
         match self.contents.get(&content.clone().cid).await {
             Ok(Some(_)) => return Err(StateError::AlreadyExists),
             _ => {}
         }
+        if self.tombstones.get(&content.cid).await?.is_some() {
+            return Err(StateError::AlreadyExists);
+        }
+        content.hot_score = self.hot_score(content.engagement, content.created_at);
+        content.tags = Self::normalize_tags(content.tags);
+        if let Some(parent_cid) = content.comment_to_cid.clone() {
+            content.depth = match self.contents.get(&parent_cid).await? {
+                Some(parent) => parent.depth + 1,
+                _ => 1,
+            };
+        }
         self.contents
             .insert(&content.clone().cid, content.clone())
             .unwrap();
+        if content.comment_to_cid.is_none() {
+            self.timeline_index.push(content.cid.clone());
+            self.index_content(&content.cid, &content.title, &content.content)
+                .await?;
+            let mut stats = self.author_stats.get(&owner).await?.unwrap_or_default();
+            stats.publications += 1;
+            self.author_stats.insert(&owner, stats)?;
+            self.rank_content(&content).await?;
+        }
         match self.publishes.get(&owner).await {
             Ok(Some(mut cids)) => {
                 cids.push(content.cid);
@@ -53,54 +264,240 @@ impl Feed {
         }
     }
 
-    pub(crate) async fn like_content(
+    fn window_start(now: Timestamp, limit: RateLimit) -> Timestamp {
+        Timestamp::from(
+            now.micros()
+                .saturating_sub(limit.window_ms.saturating_mul(1000)),
+        )
+    }
+
+    // Milliseconds to wait before owner could perform the action again
+    pub(crate) async fn cooldown_ms(
+        &self,
+        owner: Owner,
+        action: Action,
+        now: Timestamp,
+    ) -> Result<u64, StateError> {
+        let limit = self.rate_limits.get().limit(action);
+        if limit.max_actions == 0 {
+            return Ok(0);
+        }
+        let window_start = Self::window_start(now, limit);
+        let windows = self.action_windows.get(&owner).await?.unwrap_or_default();
+        let actions = windows
+            .get(&action)
+            .map(|timestamps| {
+                timestamps
+                    .iter()
+                    .filter(|&&timestamp| timestamp > window_start)
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if actions.len() < limit.max_actions as usize {
+            return Ok(0);
+        }
+        // The oldest action in window must slide out before the next one
+        let oldest = actions[actions.len() - limit.max_actions as usize];
+        Ok(oldest
+            .saturating_diff_micros(window_start)
+            .saturating_add(999)
+            / 1000)
+    }
+
+    pub(crate) async fn rate_limit(
         &mut self,
-        ccid: String,
         owner: Owner,
-        like: bool,
+        action: Action,
     ) -> Result<(), StateError> {
-        match self.react_accounts.get(&owner).await {
-            Ok(Some(reacted_at)) => {
-                if current_system_time().saturating_diff_micros(reacted_at)
-                    < *self.react_interval_ms.get()
+        let limit = self.rate_limits.get().limit(action);
+        if limit.max_actions == 0 {
+            return Ok(());
+        }
+        let now = current_system_time();
+        let window_start = Self::window_start(now, limit);
+        let mut windows = self.action_windows.get(&owner).await?.unwrap_or_default();
+        let timestamps = windows.entry(action).or_default();
+        timestamps.retain(|&timestamp| timestamp > window_start);
+        if timestamps.len() >= limit.max_actions as usize {
+            return Err(StateError::TooFrequently);
+        }
+        timestamps.push(now);
+        self.action_windows.insert(&owner, windows)?;
+        Ok(())
+    }
+
+    // Log scaled engagement plus publish time, so that score of a content never decays but newer
+    // contents get higher score, which could be maintained incrementally
+    fn hot_score(&self, engagement: i64, created_at: Timestamp) -> i64 {
+        let gravity = (*self.hot_gravity_secs.get()).max(1) as f64;
+        let order = (engagement.unsigned_abs().max(1) as f64).log10();
+        let seconds = created_at.micros() as f64 / 1_000_000.0;
+        ((engagement.signum() as f64 * order + seconds / gravity) * 1_000_000.0) as i64
+    }
+
+    fn engage(&self, content: &mut Content, engagement: i64) {
+        content.engagement = content.engagement.saturating_add(engagement);
+        content.hot_score = self.hot_score(content.engagement, content.created_at);
+    }
+
+    async fn engage_content(&mut self, cid: &String, engagement: i64) -> Result<(), StateError> {
+        if let Some(mut content) = self.contents.get(cid).await? {
+            self.engage(&mut content, engagement);
+            self.store_content(content).await?;
+        }
+        Ok(())
+    }
+
+    // Adds a top level content to ordered indices of the timeline, its author and its tags
+    async fn rank_content(&mut self, content: &Content) -> Result<(), StateError> {
+        self.hot_index
+            .insert(&RankKey::new(content, ContentSort::Hot))?;
+        self.top_index
+            .insert(&RankKey::new(content, ContentSort::Top))?;
+        self.author_contents
+            .load_entry_mut(&content.author)
+            .await?
+            .insert(content)?;
+        for tag in content.tags.iter() {
+            self.tag_contents
+                .load_entry_mut(tag)
+                .await?
+                .insert(content)?;
+        }
+        Ok(())
+    }
+
+    async fn unrank_content(&mut self, content: &Content) -> Result<(), StateError> {
+        self.hot_index
+            .remove(&RankKey::new(content, ContentSort::Hot))?;
+        self.top_index
+            .remove(&RankKey::new(content, ContentSort::Top))?;
+        self.author_contents
+            .load_entry_mut(&content.author)
+            .await?
+            .remove(content)?;
+        for tag in content.tags.iter() {
+            self.tag_contents
+                .load_entry_mut(tag)
+                .await?
+                .remove(content)?;
+        }
+        Ok(())
+    }
+
+    // Stores an engaged or reacted content, and moves it in ordered indices if its rank changed
+    async fn store_content(&mut self, content: Content) -> Result<(), StateError> {
+        if content.comment_to_cid.is_none() {
+            if let Some(stored) = self.contents.get(&content.cid).await? {
+                if stored.hot_score != content.hot_score
+                    || stored.likes != content.likes
+                    || stored.dislikes != content.dislikes
                 {
-                    return Err(StateError::TooFrequently);
+                    self.unrank_content(&stored).await?;
+                    self.rank_content(&content).await?;
                 }
             }
-            _ => {
-                self.react_accounts.insert(&owner, current_system_time())?;
+        }
+        self.contents.insert(&content.cid.clone(), content)?;
+        Ok(())
+    }
+
+    fn reaction_engagement(kind: ReactionKind) -> i64 {
+        match kind {
+            ReactionKind::Dislike => -1,
+            _ => 1,
+        }
+    }
+
+    fn count_reaction(content: &mut Content, owner: Owner, kind: ReactionKind, react: bool) {
+        let count = content.reactions.entry(kind).or_insert(0);
+        if react {
+            *count += 1;
+        } else {
+            *count = count.saturating_sub(1);
+        }
+        match kind {
+            ReactionKind::Like if react => content.likes += 1,
+            ReactionKind::Like => content.likes = content.likes.saturating_sub(1),
+            ReactionKind::Dislike if react => content.dislikes += 1,
+            ReactionKind::Dislike => content.dislikes = content.dislikes.saturating_sub(1),
+            _ => {}
+        }
+        match kind {
+            ReactionKind::Like if react => content.accounts.insert(owner, true),
+            ReactionKind::Dislike if react => content.accounts.insert(owner, false),
+            _ => content.accounts.remove(&owner),
+        };
+    }
+
+    async fn count_likes_received(&mut self, author: Owner, like: bool) -> Result<(), StateError> {
+        let mut stats = self.author_stats.get(&author).await?.unwrap_or_default();
+        stats.likes_received = match like {
+            true => stats.likes_received + 1,
+            false => stats.likes_received.saturating_sub(1),
+        };
+        self.author_stats.insert(&author, stats)?;
+        Ok(())
+    }
+
+    // Returns true if it's the first reaction of owner to the content, which could be rewarded
+    pub(crate) async fn react_content(
+        &mut self,
+        cid: String,
+        owner: Owner,
+        kind: ReactionKind,
+    ) -> Result<bool, StateError> {
+        let mut content = match self.contents.get(&cid).await? {
+            Some(content) => content,
+            _ => return Err(StateError::NotExist),
+        };
+        let liked = content.reactors.get(&owner) == Some(&ReactionKind::Like);
+        match content.reactors.get(&owner) {
+            Some(&reacted) if reacted == kind => return Err(StateError::AlreadyReacted),
+            Some(&reacted) => {
+                Self::count_reaction(&mut content, owner, reacted, false);
+                self.engage(&mut content, -Self::reaction_engagement(reacted));
             }
+            _ => {}
         }
-        match self.contents.get(&ccid).await {
-            Ok(Some(mut content)) => match content.accounts.get(&owner) {
-                Some(&_like) => {
-                    if (_like && like) || (!_like && !like) {
-                        return Err(StateError::TooManyLike);
-                    }
-                    content.accounts.insert(owner, like);
-                    if _like {
-                        content.likes -= 1;
-                        content.dislikes += 1;
-                    } else {
-                        content.likes += 1;
-                        content.dislikes -= 1;
-                    }
-                    self.contents.insert(&content.clone().cid, content)?;
-                    Ok(())
-                }
-                _ => {
-                    if like {
-                        content.likes += 1;
-                    } else {
-                        content.dislikes += 1;
-                    }
-                    content.accounts.insert(owner, like);
-                    self.contents.insert(&content.clone().cid, content)?;
-                    Ok(())
-                }
-            },
+        Self::count_reaction(&mut content, owner, kind, true);
+        self.engage(&mut content, Self::reaction_engagement(kind));
+        if liked || kind == ReactionKind::Like {
+            self.count_likes_received(content.author, !liked).await?;
+        }
+        content.reactors.insert(owner, kind);
+        self.store_content(content).await?;
+
+        let mut rewarded = self.reaction_rewards.get(&cid).await?.unwrap_or_default();
+        if !rewarded.insert(owner) {
+            return Ok(false);
+        }
+        self.reaction_rewards.insert(&cid, rewarded)?;
+        Ok(true)
+    }
+
+    pub(crate) async fn unreact_content(
+        &mut self,
+        cid: String,
+        owner: Owner,
+    ) -> Result<(), StateError> {
+        let mut content = match self.contents.get(&cid).await? {
+            Some(content) => content,
             _ => return Err(StateError::NotExist),
+        };
+        match content.reactors.remove(&owner) {
+            Some(reacted) => {
+                Self::count_reaction(&mut content, owner, reacted, false);
+                self.engage(&mut content, -Self::reaction_engagement(reacted));
+                if reacted == ReactionKind::Like {
+                    self.count_likes_received(content.author, false).await?;
+                }
+            }
+            _ => return Err(StateError::NotReacted),
         }
+        self.store_content(content).await?;
+        Ok(())
     }
 
     pub(crate) async fn recommend_content(
@@ -117,6 +514,7 @@ impl Feed {
                 self.content_recommends.insert(&cid, vec![reason_cid])?;
             }
         }
+        self.engage_content(&cid, RECOMMEND_ENGAGEMENT).await?;
         Ok(())
     }
 
@@ -134,36 +532,1677 @@ impl Feed {
                 self.content_comments.insert(&cid, vec![comment_cid])?;
             }
         }
+        if let Some(mut content) = self.contents.get(&cid).await? {
+            content.replies += 1;
+            self.engage(&mut content, COMMENT_ENGAGEMENT);
+            self.store_content(content).await?;
+        }
         Ok(())
     }
 
-    pub(crate) async fn content_author(&self, cid: String) -> Result<Owner, StateError> {
-        match self.contents.get(&cid).await {
-            Ok(Some(content)) => Ok(content.author),
-            Ok(None) => Err(StateError::InvalidContent),
-            Err(err) => Err(StateError::ViewError(err)),
+    // Replies to a comment at max depth are attached to its deepest ancestor which could be replied
+    pub(crate) async fn reply_target(&self, cid: String) -> Result<String, StateError> {
+        let max_depth = *self.max_comment_depth.get();
+        let mut cid = cid;
+        if max_depth == 0 {
+            return Ok(cid);
+        }
+        while let Some(content) = self.contents.get(&cid).await? {
+            if content.depth < max_depth {
+                break;
+            }
+            match content.comment_to_cid {
+                Some(parent_cid) => cid = parent_cid,
+                _ => break,
+            }
         }
+        Ok(cid)
     }
-}
 
-/// Attempts to debit from an account with insufficient funds.
-#[derive(Debug, Error)]
-#[error("Insufficient balance for transfer")]
-pub enum StateError {
-    #[error("Content already exists")]
-    AlreadyExists,
+    // Comments of removed content are hidden with it
+    async fn comments_of(&self, cid: &String) -> Result<Vec<Content>, StateError> {
+        let mut comments = Vec::new();
+        if self.tombstones.get(cid).await?.is_some() {
+            return Ok(comments);
+        }
+        for comment_cid in self.content_comments.get(cid).await?.unwrap_or_default() {
+            if let Some(comment) = self.contents.get(&comment_cid).await? {
+                comments.push(comment);
+            }
+        }
+        Ok(comments)
+    }
 
-    #[error("Content not exist")]
-    NotExist,
+    fn sort_comments(comments: &mut [Content], sort: CommentSort) {
+        match sort {
+            CommentSort::Time => comments.sort_by_key(|comment| comment.created_at),
+            CommentSort::Likes => comments.sort_by(|a, b| {
+                b.likes
+                    .cmp(&a.likes)
+                    .then_with(|| a.created_at.cmp(&b.created_at))
+            }),
+        }
+    }
 
-    #[error("Only 1 reaction is allowed within 1 minute")]
-    TooFrequently,
+    fn comment_node(
+        comment: Content,
+        comments: &mut HashMap<String, Vec<Content>>,
+        sort: CommentSort,
+    ) -> CommentNode {
+        let mut replies = comments.remove(&comment.cid).unwrap_or_default();
+        Self::sort_comments(&mut replies, sort);
+        CommentNode {
+            comment,
+            replies: replies
+                .into_iter()
+                .map(|reply| Self::comment_node(reply, comments, sort))
+                .collect(),
+        }
+    }
 
-    #[error("Only 1 like is allowed for each content")]
-    TooManyLike,
+    fn sort_contents(contents: &mut [Content], sort: ContentSort) {
+        match sort {
+            ContentSort::Newest => contents.sort_by(|a, b| b.created_at.cmp(&a.created_at)),
+            ContentSort::Hot => contents.sort_by(|a, b| {
+                b.hot_score
+                    .cmp(&a.hot_score)
+                    .then_with(|| b.created_at.cmp(&a.created_at))
+            }),
+            ContentSort::Top => contents.sort_by(|a, b| {
+                (b.likes as i64 - b.dislikes as i64)
+                    .cmp(&(a.likes as i64 - a.dislikes as i64))
+                    .then_with(|| b.created_at.cmp(&a.created_at))
+            }),
+        }
+    }
 
-    #[error("Invalid content")]
-    InvalidContent,
+    fn parse_cursor(after: Option<String>) -> Result<Option<usize>, StateError> {
+        match after {
+            Some(cursor) => match cursor.parse::<usize>() {
+                Ok(position) => Ok(Some(position)),
+                Err(_) => Err(StateError::InvalidCursor),
+            },
+            None => Ok(None),
+        }
+    }
+
+    // Cursor of a sorted page is the offset of its end
+    fn sorted_page(
+        mut contents: Vec<Content>,
+        sort: ContentSort,
+        first: usize,
+        after: Option<String>,
+    ) -> Result<ContentConnection, StateError> {
+        Self::sort_contents(&mut contents, sort);
+        let offset = Self::parse_cursor(after)?.unwrap_or(0);
+        let end = contents.len().min(offset.saturating_add(first));
+        let has_next_page = end < contents.len();
+        Ok(ContentConnection {
+            contents: contents.into_iter().skip(offset).take(first).collect(),
+            end_cursor: Some(end.to_string()),
+            has_next_page,
+        })
+    }
+
+    fn parse_rank_cursor(after: Option<String>) -> Result<Option<RankKey>, StateError> {
+        match after {
+            Some(cursor) => match RankKey::from_cursor(&cursor) {
+                Some(key) => Ok(Some(key)),
+                _ => Err(StateError::InvalidCursor),
+            },
+            None => Ok(None),
+        }
+    }
+
+    // Keys of an ordered index after cursor and whether there are more, keys before cursor are
+    // skipped without loading their contents
+    async fn rank_page(
+        ranks: &CustomSetView<RankKey>,
+        first: usize,
+        after: &Option<RankKey>,
+    ) -> Result<(Vec<RankKey>, bool), StateError> {
+        let mut keys = Vec::new();
+        ranks
+            .for_each_index_while(|key| {
+                if after.as_ref().map_or(true, |after| key > *after) {
+                    keys.push(key);
+                }
+                Ok(keys.len() <= first)
+            })
+            .await?;
+        let has_next_page = keys.len() > first;
+        keys.truncate(first);
+        Ok((keys, has_next_page))
+    }
+
+    // Cursor of a ranked page is its last rank key, which is stable when new contents are ranked
+    async fn ranked_connection(
+        &self,
+        keys: Vec<RankKey>,
+        has_next_page: bool,
+        after: Option<RankKey>,
+    ) -> Result<ContentConnection, StateError> {
+        let end_cursor = keys.last().or(after.as_ref()).map(RankKey::cursor);
+        let contents = self
+            .contents_of(keys.into_iter().map(|key| key.cid).collect())
+            .await?;
+        Ok(ContentConnection {
+            contents,
+            end_cursor,
+            has_next_page,
+        })
+    }
+
+    async fn contents_of(&self, cids: Vec<String>) -> Result<Vec<Content>, StateError> {
+        let mut contents = Vec::new();
+        for cid in cids {
+            if let Some(content) = self.contents.get(&cid).await? {
+                contents.push(content);
+            }
+        }
+        Ok(contents)
+    }
+
+    // Cursor is the position in timeline index, which is stable when new contents are published,
+    // then the b prefixed offset in backfill index once timeline index is exhausted
+    async fn newest_timeline(
+        &self,
+        first: usize,
+        after: Option<String>,
+    ) -> Result<ContentConnection, StateError> {
+        let (mut position, mut backfilled) = match after {
+            Some(cursor) if cursor.starts_with('b') => (
+                0,
+                cursor[1..]
+                    .parse::<usize>()
+                    .map_err(|_| StateError::InvalidCursor)?,
+            ),
+            after => (
+                Self::parse_cursor(after)?
+                    .unwrap_or(self.timeline_index.count())
+                    .min(self.timeline_index.count()),
+                0,
+            ),
+        };
+        let mut contents = Vec::new();
+        while position > 0 && contents.len() < first {
+            let start = position.saturating_sub(first - contents.len());
+            for cid in self
+                .timeline_index
+                .read(start..position)
+                .await?
+                .into_iter()
+                .rev()
+            {
+                position -= 1;
+                if let Some(content) = self.contents.get(&cid).await? {
+                    contents.push(content);
+                    if contents.len() == first {
+                        break;
+                    }
+                }
+            }
+        }
+        let backfill_count = self.backfill_index.count();
+        while backfilled < backfill_count && contents.len() < first {
+            let end = backfill_count.min(backfilled + first - contents.len());
+            for cid in self.backfill_index.read(backfilled..end).await? {
+                backfilled += 1;
+                if let Some(content) = self.contents.get(&cid).await? {
+                    contents.push(content);
+                    if contents.len() == first {
+                        break;
+                    }
+                }
+            }
+        }
+        let end_cursor = match position > 0 {
+            true => position.to_string(),
+            false => format!("b{}", backfilled),
+        };
+        Ok(ContentConnection {
+            contents,
+            end_cursor: Some(end_cursor),
+            has_next_page: position > 0 || backfilled < backfill_count,
+        })
+    }
+
+    pub(crate) async fn timeline_page(
+        &self,
+        sort: ContentSort,
+        first: usize,
+        after: Option<String>,
+    ) -> Result<ContentConnection, StateError> {
+        let ranks = match sort {
+            ContentSort::Newest => return self.newest_timeline(first, after).await,
+            ContentSort::Hot => &self.hot_index,
+            ContentSort::Top => &self.top_index,
+        };
+        let after = Self::parse_rank_cursor(after)?;
+        let (keys, has_next_page) = Self::rank_page(ranks, first, &after).await?;
+        self.ranked_connection(keys, has_next_page, after).await
+    }
+
+    pub(crate) async fn author_page(
+        &self,
+        owner: Owner,
+        sort: ContentSort,
+        first: usize,
+        after: Option<String>,
+    ) -> Result<ContentConnection, StateError> {
+        let after = Self::parse_rank_cursor(after)?;
+        let (keys, has_next_page) = {
+            let index = self.author_contents.try_load_entry(&owner).await?;
+            Self::rank_page(index.ranks(sort), first, &after).await?
+        };
+        self.ranked_connection(keys, has_next_page, after).await
+    }
+
+    pub(crate) async fn comments_page(
+        &self,
+        cid: String,
+        sort: ContentSort,
+        first: usize,
+        after: Option<String>,
+    ) -> Result<ContentConnection, StateError> {
+        let comments = self.comments_of(&cid).await?;
+        Self::sorted_page(comments, sort, first, after)
+    }
+
+    // Direct comments of the content are paginated, each of them comes with its whole reply tree,
+    // cursor is the offset of the page end
+    pub(crate) async fn comment_page(
+        &self,
+        cid: String,
+        sort: CommentSort,
+        first: usize,
+        after: Option<String>,
+    ) -> Result<CommentConnection, StateError> {
+        let mut top_comments = self.comments_of(&cid).await?;
+        let total = top_comments.len() as u64;
+        Self::sort_comments(&mut top_comments, sort);
+        let offset = Self::parse_cursor(after)?.unwrap_or(0);
+        let end = top_comments.len().min(offset.saturating_add(first));
+        let has_next_page = end < top_comments.len();
+        let top_comments = top_comments
+            .into_iter()
+            .skip(offset)
+            .take(first)
+            .collect::<Vec<_>>();
+
+        let mut comments = HashMap::new();
+        let mut pending = top_comments
+            .iter()
+            .map(|comment| comment.cid.clone())
+            .collect::<Vec<_>>();
+        while let Some(cid) = pending.pop() {
+            let replies = self.comments_of(&cid).await?;
+            pending.extend(replies.iter().map(|reply| reply.cid.clone()));
+            comments.insert(cid, replies);
+        }
+
+        Ok(CommentConnection {
+            total,
+            comments: top_comments
+                .into_iter()
+                .map(|comment| Self::comment_node(comment, &mut comments, sort))
+                .collect(),
+            end_cursor: Some(end.to_string()),
+            has_next_page,
+        })
+    }
+
+    pub(crate) async fn tip_content(
+        &mut self,
+        cid: String,
+        tipper: Owner,
+        amount: Amount,
+        credits: Amount,
+    ) -> Result<(), StateError> {
+        let author = self.content_author(cid.clone()).await?;
+        if author == tipper {
+            return Err(StateError::InvalidTipper);
+        }
+        if amount == Amount::ZERO && credits == Amount::ZERO {
+            return Err(StateError::InvalidAmount);
+        }
+        let mut tips = self.content_tips.get(&cid).await?.unwrap_or_default();
+        tips.amount = tips.amount.saturating_add(amount);
+        tips.credits = tips.credits.saturating_add(credits);
+        tips.tips.push(Tip {
+            tipper,
+            amount,
+            credits,
+            created_at: current_system_time(),
+        });
+        self.content_tips.insert(&cid, tips)?;
+        let shares = self.content_shares(cid.clone()).await?;
+        let credits = split_amount(credits, &shares);
+        for (index, (author, amount)) in split_amount(amount, &shares).into_iter().enumerate() {
+            let mut stats = self.author_stats.get(&author).await?.unwrap_or_default();
+            stats.tips_received = stats.tips_received.saturating_add(amount);
+            stats.tip_credits_received =
+                stats.tip_credits_received.saturating_add(credits[index].1);
+            self.author_stats.insert(&author, stats)?;
+        }
+        self.engage_content(&cid, TIP_ENGAGEMENT).await?;
+        Ok(())
+    }
+
+    pub(crate) async fn set_premium(
+        &mut self,
+        cid: String,
+        author: Owner,
+        price: Option<PremiumPrice>,
+    ) -> Result<(), StateError> {
+        let mut content = match self.contents.get(&cid).await? {
+            Some(content) => content,
+            _ => return Err(StateError::NotExist),
+        };
+        if content.author != author {
+            return Err(StateError::NotAuthor);
+        }
+        if let Some(price) = price {
+            if price.amount == Amount::ZERO && price.credits == Amount::ZERO {
+                return Err(StateError::InvalidAmount);
+            }
+        }
+        content.price = price;
+        self.contents.insert(&cid, content)?;
+        Ok(())
+    }
+
+    // Returns price paid by reader, which should be transferred to author
+    pub(crate) async fn unlock_content(
+        &mut self,
+        cid: String,
+        reader: Owner,
+    ) -> Result<PremiumPrice, StateError> {
+        let content = match self.contents.get(&cid).await? {
+            Some(content) => content,
+            _ => return Err(StateError::NotExist),
+        };
+        let price = match content.price {
+            Some(price) => price,
+            _ => return Err(StateError::NotPremium),
+        };
+        if self.unlocked(cid.clone(), reader).await? {
+            return Err(StateError::AlreadyUnlocked);
+        }
+        let mut unlocks = self.unlocks.get(&cid).await?.unwrap_or_default();
+        unlocks.push(Unlock {
+            reader,
+            amount: price.amount,
+            credits: price.credits,
+            unlocked_at: current_system_time(),
+        });
+        self.unlocks.insert(&cid, unlocks)?;
+        Ok(price)
+    }
+
+    // Author and readers who paid could access premium content, free content is open to everyone
+    pub(crate) async fn unlocked(&self, cid: String, reader: Owner) -> Result<bool, StateError> {
+        let content = match self.contents.get(&cid).await? {
+            Some(content) => content,
+            _ => return Err(StateError::NotExist),
+        };
+        if content.price.is_none() || content.author == reader {
+            return Ok(true);
+        }
+        Ok(self
+            .unlocks
+            .get(&cid)
+            .await?
+            .unwrap_or_default()
+            .iter()
+            .any(|unlock| unlock.reader == reader))
+    }
+
+    pub(crate) async fn report_content(
+        &mut self,
+        cid: String,
+        reporter: Owner,
+        reason: String,
+    ) -> Result<Option<Vec<String>>, StateError> {
+        if self.content_author(cid.clone()).await? == reporter {
+            return Err(StateError::InvalidReporter);
+        }
+        let mut reports = match self.reports.get(&cid).await? {
+            Some(reports) => reports,
+            _ => ContentReports {
+                cid: cid.clone(),
+                status: ReportStatus::Open,
+                reports: Vec::new(),
+                flagged_at: None,
+            },
+        };
+        match reports.status {
+            ReportStatus::Open | ReportStatus::Flagged => {}
+            _ => return Err(StateError::ReportsClosed),
+        }
+        if reports
+            .reports
+            .iter()
+            .any(|report| report.reporter == reporter)
+        {
+            return Err(StateError::AlreadyReported);
+        }
+        let now = current_system_time();
+        reports.reports.push(Report {
+            reporter,
+            reason,
+            created_at: now,
+        });
+        let threshold = *self.report_threshold.get() as usize;
+        let mut flagged = None;
+        if reports.status == ReportStatus::Open
+            && threshold > 0
+            && reports.reports.len() >= threshold
+        {
+            reports.status = ReportStatus::Flagged;
+            reports.flagged_at = Some(now);
+            flagged = Some(
+                reports
+                    .reports
+                    .iter()
+                    .map(|report| report.reason.clone())
+                    .collect(),
+            );
+        }
+        self.reports.insert(&cid, reports)?;
+        Ok(flagged)
+    }
+
+    // Returns reporters who should be penalized
+    pub(crate) async fn dismiss_reports(&mut self, cid: String) -> Result<Vec<Owner>, StateError> {
+        let mut reports = match self.reports.get(&cid).await? {
+            Some(reports) => reports,
+            _ => return Ok(Vec::new()),
+        };
+        match reports.status {
+            ReportStatus::Open | ReportStatus::Flagged => {}
+            _ => return Err(StateError::ReportsClosed),
+        }
+        reports.status = ReportStatus::Dismissed;
+        let reporters = reports
+            .reports
+            .iter()
+            .map(|report| report.reporter)
+            .collect();
+        self.reports.insert(&cid, reports)?;
+        Ok(reporters)
+    }
+
+    // Most reported first
+    pub(crate) async fn reported_contents(
+        &self,
+        status: Option<ReportStatus>,
+    ) -> Result<Vec<ContentReports>, StateError> {
+        let mut contents = Vec::new();
+        for cid in self.reports.indices().await? {
+            if let Some(reports) = self.reports.get(&cid).await? {
+                if status.is_none() || status == Some(reports.status) {
+                    contents.push(reports);
+                }
+            }
+        }
+        contents.sort_by(|a, b| b.reports.len().cmp(&a.reports.len()));
+        Ok(contents)
+    }
+
+    // Platform fee which will be deposited to foundation
+    pub(crate) fn tip_fee(&self, amount: Amount) -> Amount {
+        Amount::from_atto(
+            amount
+                .saturating_mul(*self.tip_fee_percent.get() as u128)
+                .saturating_div(Amount::from_atto(100)),
+        )
+    }
+
+    pub(crate) async fn original_cid(&self, cid: String) -> Result<String, StateError> {
+        Ok(self.revision_of.get(&cid).await?.unwrap_or(cid))
+    }
+
+    pub(crate) async fn edit_content(
+        &mut self,
+        cid: String,
+        new_cid: String,
+        title: String,
+        content: String,
+        author: Owner,
+        reviewed: bool,
+    ) -> Result<(), StateError> {
+        if !reviewed && *self.edit_requires_review.get() {
+            return Err(StateError::EditRequiresReview);
+        }
+        self.check_content_cid(&new_cid, &content)?;
+        let body_size = content.len() as u64;
+        let (content, body_storage) = self.store_body(content)?;
+        let cid = self.original_cid(cid).await?;
+        if self.contents.get(&new_cid).await?.is_some()
+            || self.revision_of.get(&new_cid).await?.is_some()
+        {
+            return Err(StateError::AlreadyExists);
+        }
+        let mut original = match self.contents.get(&cid).await? {
+            Some(original) => original,
+            _ => return Err(StateError::NotExist),
+        };
+        if original.author != author {
+            return Err(StateError::NotAuthor);
+        }
+        let mut revisions = self.revisions.get(&cid).await?.unwrap_or_default();
+        if revisions.is_empty() {
+            revisions.push(Revision {
+                cid: cid.clone(),
+                title: original.title.clone(),
+                content: original.content.clone(),
+                body_storage: original.body_storage,
+                created_at: original.created_at,
+            });
+        }
+        let now = current_system_time();
+        revisions.push(Revision {
+            cid: new_cid.clone(),
+            title: title.clone(),
+            content: content.clone(),
+            body_storage,
+            created_at: now,
+        });
+        if original.comment_to_cid.is_none() {
+            self.unindex_content(&cid, &original.title, &original.content)
+                .await?;
+            self.index_content(&cid, &title, &content).await?;
+        }
+        original.title = title;
+        original.content = content;
+        original.body_storage = body_storage;
+        original.body_size = body_size;
+        original.revision_cid = Some(new_cid.clone());
+        original.edited_at = Some(now);
+        self.contents.insert(&cid, original)?;
+        self.revisions.insert(&cid, revisions)?;
+        self.revision_of.insert(&new_cid, cid)?;
+        Ok(())
+    }
+
+    pub(crate) async fn revision_history(&self, cid: String) -> Result<Vec<Revision>, StateError> {
+        let cid = self.original_cid(cid).await?;
+        Ok(self.revisions.get(&cid).await?.unwrap_or_default())
+    }
+
+    pub(crate) fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+        let mut normalized: Vec<String> = Vec::new();
+        for tag in tags {
+            let tag = tag.trim().to_lowercase();
+            if !tag.is_empty() && !normalized.contains(&tag) {
+                normalized.push(tag);
+            }
+        }
+        normalized
+    }
+
+    pub(crate) async fn check_tags(
+        &self,
+        cid: String,
+        tags: Vec<String>,
+    ) -> Result<(), StateError> {
+        let content = match self.contents.get(&cid).await? {
+            Some(content) => content,
+            _ => return Err(StateError::NotExist),
+        };
+        let tags = Self::normalize_tags(tags);
+        match tags.iter().any(|tag| content.tags.contains(tag)) {
+            true => Ok(()),
+            false => Err(StateError::TagMismatch),
+        }
+    }
+
+    pub(crate) async fn tag_page(
+        &self,
+        tag: String,
+        sort: ContentSort,
+        first: usize,
+        after: Option<String>,
+    ) -> Result<ContentConnection, StateError> {
+        let tag = tag.trim().to_lowercase();
+        let after = Self::parse_rank_cursor(after)?;
+        let (keys, has_next_page) = {
+            let index = self.tag_contents.try_load_entry(&tag).await?;
+            Self::rank_page(index.ranks(sort), first, &after).await?
+        };
+        self.ranked_connection(keys, has_next_page, after).await
+    }
+
+    pub(crate) async fn subscribe_topics(
+        &mut self,
+        chain_id: ChainId,
+        topics: Topics,
+    ) -> Result<(), StateError> {
+        let topics = Topics {
+            authors: topics.authors,
+            tags: Self::normalize_tags(topics.tags),
+        };
+        if topics.authors.len() + topics.tags.len() > MAX_TOPICS {
+            return Err(StateError::TooManyTopics);
+        }
+        if let Some(previous) = self.subscriber_topics.get(&chain_id).await? {
+            for author in previous.authors {
+                let mut chain_ids = self
+                    .author_subscribers
+                    .get(&author)
+                    .await?
+                    .unwrap_or_default();
+                chain_ids.remove(&chain_id);
+                match chain_ids.is_empty() {
+                    true => self.author_subscribers.remove(&author)?,
+                    false => self.author_subscribers.insert(&author, chain_ids)?,
+                }
+            }
+            for tag in previous.tags {
+                let mut chain_ids = self.tag_subscribers.get(&tag).await?.unwrap_or_default();
+                chain_ids.remove(&chain_id);
+                match chain_ids.is_empty() {
+                    true => self.tag_subscribers.remove(&tag)?,
+                    false => self.tag_subscribers.insert(&tag, chain_ids)?,
+                }
+            }
+        }
+        if topics.is_empty() {
+            self.subscriber_topics.remove(&chain_id)?;
+            return Ok(());
+        }
+        for author in topics.authors.iter() {
+            let mut chain_ids = self
+                .author_subscribers
+                .get(author)
+                .await?
+                .unwrap_or_default();
+            chain_ids.insert(chain_id);
+            self.author_subscribers.insert(author, chain_ids)?;
+        }
+        for tag in topics.tags.iter() {
+            let mut chain_ids = self.tag_subscribers.get(tag).await?.unwrap_or_default();
+            chain_ids.insert(chain_id);
+            self.tag_subscribers.insert(tag, chain_ids)?;
+        }
+        self.subscriber_topics.insert(&chain_id, topics)?;
+        Ok(())
+    }
+
+    pub(crate) async fn topic_subscribers(
+        &self,
+        author: Owner,
+        tags: Vec<String>,
+    ) -> Result<HashSet<ChainId>, StateError> {
+        let mut chain_ids = self
+            .author_subscribers
+            .get(&author)
+            .await?
+            .unwrap_or_default();
+        for tag in tags {
+            chain_ids.extend(self.tag_subscribers.get(&tag).await?.unwrap_or_default());
+        }
+        Ok(chain_ids)
+    }
+
+    // Comments and recommends belong to the topics of the root content they reply to
+    pub(crate) async fn content_topic(
+        &self,
+        cid: String,
+    ) -> Result<Option<(Owner, Vec<String>)>, StateError> {
+        let mut cid = self.original_cid(cid).await?;
+        while let Some(content) = self.contents.get(&cid).await? {
+            match content.comment_to_cid {
+                Some(parent_cid) => cid = parent_cid,
+                _ => return Ok(Some((content.author, content.tags))),
+            }
+        }
+        Ok(None)
+    }
+
+    pub(crate) async fn initial_state(&self) -> Result<InitialState, StateError> {
+        Ok(InitialState {
+            rate_limits: self.rate_limits.get().clone(),
+            tip_fee_percent: *self.tip_fee_percent.get(),
+            max_comment_depth: *self.max_comment_depth.get(),
+            edit_requires_review: *self.edit_requires_review.get(),
+            clawback_author_reward: *self.clawback_author_reward.get(),
+            hot_gravity_secs: *self.hot_gravity_secs.get(),
+            verify_content_cid: *self.verify_content_cid.get(),
+            max_body_bytes: *self.max_body_bytes.get(),
+            offload_body: *self.offload_body.get(),
+            excerpt_bytes: *self.excerpt_bytes.get(),
+            report_threshold: *self.report_threshold.get(),
+            bad_report_penalty: *self.bad_report_penalty.get(),
+        })
+    }
+
+    // Scans at most MAX_BACKFILL_SCAN timeline entries before cursor for contents of the subscriber topics
+    pub(crate) async fn backfill_page(
+        &self,
+        chain_id: ChainId,
+        first: usize,
+        cursor: Option<u64>,
+    ) -> Result<(Vec<Content>, Option<u64>), StateError> {
+        let topics = self.subscriber_topics.get(&chain_id).await?;
+        let mut position = cursor
+            .map_or(self.timeline_index.count(), |cursor| cursor as usize)
+            .min(self.timeline_index.count());
+        let end = position.saturating_sub(MAX_BACKFILL_SCAN);
+        let mut contents = Vec::new();
+        for cid in self
+            .timeline_index
+            .read(end..position)
+            .await?
+            .into_iter()
+            .rev()
+        {
+            if contents.len() == first {
+                break;
+            }
+            position -= 1;
+            if let Some(content) = self.contents.get(&cid).await? {
+                if topics
+                    .as_ref()
+                    .map_or(true, |topics| topics.matches(content.author, &content.tags))
+                {
+                    contents.push(content);
+                }
+            }
+        }
+        Ok((contents, (position > 0).then_some(position as u64)))
+    }
+
+    // Backfilled contents are already processed by creation chain, so they are stored as they are
+    pub(crate) async fn backfill(
+        &mut self,
+        contents: Vec<Content>,
+        cursor: Option<u64>,
+    ) -> Result<(), StateError> {
+        for content in contents {
+            if self.contents.get(&content.cid).await?.is_some()
+                || self.tombstones.get(&content.cid).await?.is_some()
+            {
+                continue;
+            }
+            self.backfill_index.push(content.cid.clone());
+            let mut stats = self
+                .author_stats
+                .get(&content.author)
+                .await?
+                .unwrap_or_default();
+            stats.publications += 1;
+            self.author_stats.insert(&content.author, stats)?;
+            self.index_content(&content.cid, &content.title, &content.content)
+                .await?;
+            self.rank_content(&content).await?;
+            let mut cids = self
+                .publishes
+                .get(&content.author)
+                .await?
+                .unwrap_or_default();
+            cids.push(content.cid.clone());
+            self.publishes.insert(&content.author, cids)?;
+            self.contents.insert(&content.cid.clone(), content)?;
+        }
+        self.backfill_cursor.set(cursor);
+        Ok(())
+    }
+
+    // Only the excerpt is indexed when body is offloaded
+    fn term_frequencies(title: &str, content: &str) -> HashMap<String, u32> {
+        let mut frequencies = HashMap::new();
+        for term in tokenize(title) {
+            *frequencies.entry(term).or_default() += TITLE_TERM_WEIGHT;
+        }
+        for term in tokenize(content) {
+            *frequencies.entry(term).or_default() += 1;
+        }
+        frequencies
+    }
+
+    async fn index_content(
+        &mut self,
+        cid: &String,
+        title: &str,
+        content: &str,
+    ) -> Result<(), StateError> {
+        for (term, frequency) in Self::term_frequencies(title, content) {
+            let mut postings = self.search_index.get(&term).await?.unwrap_or_default();
+            postings.insert(cid.clone(), frequency);
+            self.search_index.insert(&term, postings)?;
+        }
+        Ok(())
+    }
+
+    async fn unindex_content(
+        &mut self,
+        cid: &String,
+        title: &str,
+        content: &str,
+    ) -> Result<(), StateError> {
+        for term in Self::term_frequencies(title, content).into_keys() {
+            if let Some(mut postings) = self.search_index.get(&term).await? {
+                postings.remove(cid);
+                match postings.is_empty() {
+                    true => self.search_index.remove(&term)?,
+                    false => self.search_index.insert(&term, postings)?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Ranked by tf-idf of query terms, cursor is the offset of the page end
+    pub(crate) async fn search_page(
+        &self,
+        query: String,
+        author: Option<Owner>,
+        tag: Option<String>,
+        since: Option<Timestamp>,
+        until: Option<Timestamp>,
+        first: usize,
+        after: Option<String>,
+    ) -> Result<ContentConnection, StateError> {
+        let mut terms = tokenize(&query);
+        terms.sort();
+        terms.dedup();
+        terms.truncate(MAX_QUERY_TERMS);
+        let total = (self.timeline_index.count() + self.backfill_index.count()).max(1) as f64;
+        let mut scores: HashMap<String, f64> = HashMap::new();
+        for term in terms {
+            let postings = self.search_index.get(&term).await?.unwrap_or_default();
+            if postings.is_empty() {
+                continue;
+            }
+            let idf = (1.0 + total / postings.len() as f64).ln();
+            for (cid, frequency) in postings {
+                *scores.entry(cid).or_default() += (1.0 + (frequency as f64).ln()) * idf;
+            }
+        }
+        let tag = tag.map(|tag| tag.trim().to_lowercase());
+        let mut hits = Vec::new();
+        for (cid, score) in scores {
+            let content = match self.contents.get(&cid).await? {
+                Some(content) => content,
+                _ => continue,
+            };
+            if let Some(author) = author {
+                if content.author != author
+                    && !content.authors.iter().any(|share| share.owner == author)
+                {
+                    continue;
+                }
+            }
+            if let Some(tag) = &tag {
+                if !content.tags.contains(tag) {
+                    continue;
+                }
+            }
+            if since.map_or(false, |since| content.created_at < since)
+                || until.map_or(false, |until| content.created_at > until)
+            {
+                continue;
+            }
+            hits.push((score, content));
+        }
+        hits.sort_by(|a, b| {
+            b.0.partial_cmp(&a.0)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.1.created_at.cmp(&a.1.created_at))
+        });
+        let offset = Self::parse_cursor(after)?.unwrap_or(0);
+        let end = hits.len().min(offset.saturating_add(first));
+        let has_next_page = end < hits.len();
+        Ok(ContentConnection {
+            contents: hits
+                .into_iter()
+                .skip(offset)
+                .take(first)
+                .map(|(_, content)| content)
+                .collect(),
+            end_cursor: Some(end.to_string()),
+            has_next_page,
+        })
+    }
+
+    pub(crate) async fn follow(
+        &mut self,
+        follower: Owner,
+        author: Owner,
+    ) -> Result<(), StateError> {
+        if follower == author {
+            return Err(StateError::InvalidFollower);
+        }
+        let mut followings = self.followings.get(&follower).await?.unwrap_or_default();
+        if !followings.insert(author) {
+            return Err(StateError::AlreadyFollowed);
+        }
+        let mut followers = self.followers.get(&author).await?.unwrap_or_default();
+        followers.insert(follower);
+        self.followings.insert(&follower, followings)?;
+        self.followers.insert(&author, followers)?;
+        Ok(())
+    }
+
+    pub(crate) async fn unfollow(
+        &mut self,
+        follower: Owner,
+        author: Owner,
+    ) -> Result<(), StateError> {
+        let mut followings = self.followings.get(&follower).await?.unwrap_or_default();
+        if !followings.remove(&author) {
+            return Err(StateError::NotFollowed);
+        }
+        let mut followers = self.followers.get(&author).await?.unwrap_or_default();
+        followers.remove(&follower);
+        self.followings.insert(&follower, followings)?;
+        self.followers.insert(&author, followers)?;
+        Ok(())
+    }
+
+    pub(crate) async fn follow_stats(&self, owner: Owner) -> Result<FollowStats, StateError> {
+        Ok(FollowStats {
+            followers: self.followers.get(&owner).await?.unwrap_or_default().len() as u64,
+            followings: self.followings.get(&owner).await?.unwrap_or_default().len() as u64,
+        })
+    }
+
+    // Newest top level publications of authors followed by owner, merged from a page of each
+    // author after the same cursor
+    pub(crate) async fn following_page(
+        &self,
+        owner: Owner,
+        first: usize,
+        after: Option<String>,
+    ) -> Result<ContentConnection, StateError> {
+        let after = Self::parse_rank_cursor(after)?;
+        let mut keys = Vec::new();
+        let mut has_next_page = false;
+        for author in self.followings.get(&owner).await?.unwrap_or_default() {
+            let index = self.author_contents.try_load_entry(&author).await?;
+            let (author_keys, has_more) = Self::rank_page(&index.newest, first, &after).await?;
+            keys.extend(author_keys);
+            has_next_page |= has_more;
+        }
+        keys.sort();
+        has_next_page |= keys.len() > first;
+        keys.truncate(first);
+        self.ranked_connection(keys, has_next_page, after).await
+    }
+
+    pub(crate) async fn update_profile(
+        &mut self,
+        owner: Owner,
+        display_name: String,
+        bio: String,
+        links: Vec<String>,
+        avatar: Option<Avatar>,
+    ) -> Result<(), StateError> {
+        let display_name = display_name.trim().to_string();
+        if display_name.chars().count() > MAX_DISPLAY_NAME_LEN
+            || bio.chars().count() > MAX_BIO_LEN
+            || links.len() > MAX_LINKS
+        {
+            return Err(StateError::InvalidProfile);
+        }
+        self.profiles.insert(
+            &owner,
+            Profile {
+                owner,
+                display_name,
+                bio,
+                links,
+                avatar,
+                updated_at: current_system_time(),
+            },
+        )?;
+        Ok(())
+    }
+
+    pub(crate) async fn author_profile(&self, owner: Owner) -> Result<AuthorProfile, StateError> {
+        Ok(AuthorProfile {
+            owner,
+            profile: self.profiles.get(&owner).await?,
+            stats: self.author_stats.get(&owner).await?.unwrap_or_default(),
+            follow_stats: self.follow_stats(owner).await?,
+        })
+    }
+
+    pub(crate) async fn bookmark(&mut self, owner: Owner, cid: String) -> Result<(), StateError> {
+        if self.contents.get(&cid).await?.is_none() {
+            return Err(StateError::NotExist);
+        }
+        let mut cids = self.bookmarks.get(&owner).await?.unwrap_or_default();
+        if cids.contains(&cid) {
+            return Err(StateError::AlreadyBookmarked);
+        }
+        cids.push(cid);
+        self.bookmarks.insert(&owner, cids)?;
+        Ok(())
+    }
+
+    pub(crate) async fn unbookmark(&mut self, owner: Owner, cid: String) -> Result<(), StateError> {
+        let mut cids = self.bookmarks.get(&owner).await?.unwrap_or_default();
+        match cids.iter().position(|item| *item == cid) {
+            Some(index) => cids.remove(index),
+            _ => return Err(StateError::NotBookmarked),
+        };
+        self.bookmarks.insert(&owner, cids)?;
+        Ok(())
+    }
+
+    // Most recently bookmarked first, removed contents are skipped
+    pub(crate) async fn bookmarked_contents(
+        &self,
+        owner: Owner,
+    ) -> Result<Vec<Content>, StateError> {
+        let cids = self.bookmarks.get(&owner).await?.unwrap_or_default();
+        let mut contents = Vec::new();
+        for cid in cids.iter().rev() {
+            if let Some(content) = self.contents.get(cid).await? {
+                contents.push(content);
+            }
+        }
+        Ok(contents)
+    }
+
+    pub(crate) async fn create_reading_list(
+        &mut self,
+        owner: Owner,
+        name: String,
+        description: String,
+        public: bool,
+    ) -> Result<u64, StateError> {
+        let name = name.trim().to_string();
+        if name.is_empty()
+            || name.chars().count() > MAX_READING_LIST_NAME_LEN
+            || description.chars().count() > MAX_READING_LIST_DESCRIPTION_LEN
+        {
+            return Err(StateError::InvalidReadingList);
+        }
+        let list_id = self.reading_list_id.get() + 1;
+        self.reading_list_id.set(list_id);
+        let now = current_system_time();
+        self.reading_lists.insert(
+            &list_id,
+            ReadingList {
+                id: list_id,
+                owner,
+                name,
+                description,
+                public,
+                cids: Vec::new(),
+                created_at: now,
+                updated_at: now,
+            },
+        )?;
+        let mut list_ids = self
+            .owner_reading_list_ids
+            .get(&owner)
+            .await?
+            .unwrap_or_default();
+        list_ids.push(list_id);
+        self.owner_reading_list_ids.insert(&owner, list_ids)?;
+        Ok(list_id)
+    }
+
+    async fn owned_reading_list(
+        &self,
+        owner: Owner,
+        list_id: u64,
+    ) -> Result<ReadingList, StateError> {
+        match self.reading_lists.get(&list_id).await? {
+            Some(list) if list.owner == owner => Ok(list),
+            Some(_) => Err(StateError::NotReadingListOwner),
+            _ => Err(StateError::ReadingListNotExist),
+        }
+    }
+
+    pub(crate) async fn delete_reading_list(
+        &mut self,
+        owner: Owner,
+        list_id: u64,
+    ) -> Result<(), StateError> {
+        self.owned_reading_list(owner, list_id).await?;
+        self.reading_lists.remove(&list_id)?;
+        let mut list_ids = self
+            .owner_reading_list_ids
+            .get(&owner)
+            .await?
+            .unwrap_or_default();
+        list_ids.retain(|id| *id != list_id);
+        self.owner_reading_list_ids.insert(&owner, list_ids)?;
+        Ok(())
+    }
+
+    pub(crate) async fn add_to_reading_list(
+        &mut self,
+        owner: Owner,
+        list_id: u64,
+        cid: String,
+    ) -> Result<(), StateError> {
+        let mut list = self.owned_reading_list(owner, list_id).await?;
+        if self.contents.get(&cid).await?.is_none() {
+            return Err(StateError::NotExist);
+        }
+        if list.cids.contains(&cid) {
+            return Err(StateError::AlreadyInReadingList);
+        }
+        list.cids.push(cid);
+        list.updated_at = current_system_time();
+        self.reading_lists.insert(&list_id, list)?;
+        Ok(())
+    }
+
+    pub(crate) async fn remove_from_reading_list(
+        &mut self,
+        owner: Owner,
+        list_id: u64,
+        cid: String,
+    ) -> Result<(), StateError> {
+        let mut list = self.owned_reading_list(owner, list_id).await?;
+        match list.cids.iter().position(|item| *item == cid) {
+            Some(index) => list.cids.remove(index),
+            _ => return Err(StateError::NotInReadingList),
+        };
+        list.updated_at = current_system_time();
+        self.reading_lists.insert(&list_id, list)?;
+        Ok(())
+    }
+
+    // Returns false if cid is not in cids
+    fn move_cid(cids: &mut Vec<String>, cid: String, position: u32) -> bool {
+        let cid = match cids.iter().position(|item| *item == cid) {
+            Some(index) => cids.remove(index),
+            _ => return false,
+        };
+        let position = (position as usize).min(cids.len());
+        cids.insert(position, cid);
+        true
+    }
+
+    pub(crate) async fn move_reading_list_item(
+        &mut self,
+        owner: Owner,
+        list_id: u64,
+        cid: String,
+        position: u32,
+    ) -> Result<(), StateError> {
+        let mut list = self.owned_reading_list(owner, list_id).await?;
+        if !Self::move_cid(&mut list.cids, cid, position) {
+            return Err(StateError::NotInReadingList);
+        }
+        list.updated_at = current_system_time();
+        self.reading_lists.insert(&list_id, list)?;
+        Ok(())
+    }
+
+    pub(crate) async fn reading_lists_of(
+        &self,
+        owner: Owner,
+        include_private: bool,
+    ) -> Result<Vec<ReadingList>, StateError> {
+        let list_ids = self
+            .owner_reading_list_ids
+            .get(&owner)
+            .await?
+            .unwrap_or_default();
+        let mut lists = Vec::new();
+        for list_id in list_ids {
+            if let Some(list) = self.reading_lists.get(&list_id).await? {
+                if list.public || include_private {
+                    lists.push(list);
+                }
+            }
+        }
+        Ok(lists)
+    }
+
+    // Newest public lists of all users
+    pub(crate) async fn public_reading_lists(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<ReadingList>, StateError> {
+        let mut lists = Vec::new();
+        let mut skipped = 0;
+        let mut list_id = *self.reading_list_id.get();
+        while list_id > 0 && lists.len() < limit {
+            if let Some(list) = self.reading_lists.get(&list_id).await? {
+                if list.public {
+                    if skipped < offset {
+                        skipped += 1;
+                    } else {
+                        lists.push(list);
+                    }
+                }
+            }
+            list_id -= 1;
+        }
+        Ok(lists)
+    }
+
+    // Contents of the list in reading order, removed contents are skipped
+    pub(crate) async fn reading_list_contents(
+        &self,
+        list_id: u64,
+    ) -> Result<Vec<Content>, StateError> {
+        let list = match self.reading_lists.get(&list_id).await? {
+            Some(list) => list,
+            _ => return Err(StateError::ReadingListNotExist),
+        };
+        let mut contents = Vec::new();
+        for cid in list.cids.iter() {
+            if let Some(content) = self.contents.get(cid).await? {
+                contents.push(content);
+            }
+        }
+        Ok(contents)
+    }
+
+    pub(crate) async fn create_series(
+        &mut self,
+        author: Owner,
+        title: String,
+        description: String,
+    ) -> Result<u64, StateError> {
+        let title = title.trim().to_string();
+        if title.is_empty()
+            || title.chars().count() > MAX_SERIES_TITLE_LEN
+            || description.chars().count() > MAX_SERIES_DESCRIPTION_LEN
+        {
+            return Err(StateError::InvalidSeries);
+        }
+        let series_id = self.series_id.get() + 1;
+        self.series_id.set(series_id);
+        let now = current_system_time();
+        self.series.insert(
+            &series_id,
+            Series {
+                id: series_id,
+                author,
+                title,
+                description,
+                cids: Vec::new(),
+                created_at: now,
+                updated_at: now,
+            },
+        )?;
+        let mut series_ids = self.author_series.get(&author).await?.unwrap_or_default();
+        series_ids.push(series_id);
+        self.author_series.insert(&author, series_ids)?;
+        Ok(series_id)
+    }
+
+    async fn authored_series(&self, author: Owner, series_id: u64) -> Result<Series, StateError> {
+        match self.series.get(&series_id).await? {
+            Some(series) if series.author == author => Ok(series),
+            Some(_) => Err(StateError::NotSeriesAuthor),
+            _ => Err(StateError::SeriesNotExist),
+        }
+    }
+
+    // Only top level contents of the series author could be appended, each to one series at most
+    pub(crate) async fn append_to_series(
+        &mut self,
+        author: Owner,
+        series_id: u64,
+        cid: String,
+    ) -> Result<(), StateError> {
+        let mut series = self.authored_series(author, series_id).await?;
+        match self.contents.get(&cid).await? {
+            Some(content) if content.author != author => return Err(StateError::NotAuthor),
+            Some(content) if content.comment_to_cid.is_some() => {
+                return Err(StateError::InvalidContent)
+            }
+            Some(_) => {}
+            _ => return Err(StateError::NotExist),
+        }
+        if self.series_of_content.get(&cid).await?.is_some() {
+            return Err(StateError::AlreadyInSeries);
+        }
+        series.cids.push(cid.clone());
+        series.updated_at = current_system_time();
+        self.series.insert(&series_id, series)?;
+        self.series_of_content.insert(&cid, series_id)?;
+        Ok(())
+    }
+
+    pub(crate) async fn remove_from_series(
+        &mut self,
+        author: Owner,
+        series_id: u64,
+        cid: String,
+    ) -> Result<(), StateError> {
+        let mut series = self.authored_series(author, series_id).await?;
+        match series.cids.iter().position(|item| *item == cid) {
+            Some(index) => series.cids.remove(index),
+            _ => return Err(StateError::NotInSeries),
+        };
+        series.updated_at = current_system_time();
+        self.series.insert(&series_id, series)?;
+        self.series_of_content.remove(&cid)?;
+        Ok(())
+    }
+
+    pub(crate) async fn move_series_item(
+        &mut self,
+        author: Owner,
+        series_id: u64,
+        cid: String,
+        position: u32,
+    ) -> Result<(), StateError> {
+        let mut series = self.authored_series(author, series_id).await?;
+        if !Self::move_cid(&mut series.cids, cid, position) {
+            return Err(StateError::NotInSeries);
+        }
+        series.updated_at = current_system_time();
+        self.series.insert(&series_id, series)?;
+        Ok(())
+    }
+
+    pub(crate) async fn series_of(&self, author: Owner) -> Result<Vec<Series>, StateError> {
+        let mut series = Vec::new();
+        for series_id in self.author_series.get(&author).await?.unwrap_or_default() {
+            if let Some(item) = self.series.get(&series_id).await? {
+                series.push(item);
+            }
+        }
+        Ok(series)
+    }
+
+    fn series_item(series: &Series, index: usize, content: Option<Content>) -> SeriesItem {
+        SeriesItem {
+            cid: series.cids[index].clone(),
+            position: index as u32,
+            content,
+            previous: match index {
+                0 => None,
+                _ => series.cids.get(index - 1).cloned(),
+            },
+            next: series.cids.get(index + 1).cloned(),
+        }
+    }
+
+    pub(crate) async fn series_detail(&self, series_id: u64) -> Result<SeriesDetail, StateError> {
+        let series = match self.series.get(&series_id).await? {
+            Some(series) => series,
+            _ => return Err(StateError::SeriesNotExist),
+        };
+        let mut items = Vec::new();
+        for (index, cid) in series.cids.iter().enumerate() {
+            let content = self.contents.get(cid).await?;
+            items.push(Self::series_item(&series, index, content));
+        }
+        Ok(SeriesDetail { series, items })
+    }
+
+    // Series of the content with only the item of the content
+    pub(crate) async fn series_navigation(
+        &self,
+        cid: String,
+    ) -> Result<Option<SeriesDetail>, StateError> {
+        let series = match self.series_of_content.get(&cid).await? {
+            Some(series_id) => match self.series.get(&series_id).await? {
+                Some(series) => series,
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        let index = match series.cids.iter().position(|item| *item == cid) {
+            Some(index) => index,
+            _ => return Ok(None),
+        };
+        let content = self.contents.get(&cid).await?;
+        let item = Self::series_item(&series, index, content);
+        Ok(Some(SeriesDetail {
+            series,
+            items: vec![item],
+        }))
+    }
+
+    // Tombstone the content and detach it from author publishes and its parent,
+    // return the original cid of the removed content
+    // Publication, likes and tips of removed content no longer count for its authors
+    async fn uncount_author_stats(&mut self, content: &Content) -> Result<(), StateError> {
+        let mut stats = self
+            .author_stats
+            .get(&content.author)
+            .await?
+            .unwrap_or_default();
+        if content.comment_to_cid.is_none() {
+            stats.publications = stats.publications.saturating_sub(1);
+        }
+        stats.likes_received = stats.likes_received.saturating_sub(content.likes);
+        self.author_stats.insert(&content.author, stats)?;
+        let tips = match self.content_tips.get(&content.cid).await? {
+            Some(tips) => tips,
+            None => return Ok(()),
+        };
+        let shares = content.shares();
+        let credits = split_amount(tips.credits, &shares);
+        for (index, (author, amount)) in split_amount(tips.amount, &shares).into_iter().enumerate()
+        {
+            let mut stats = self.author_stats.get(&author).await?.unwrap_or_default();
+            stats.tips_received = stats.tips_received.saturating_sub(amount);
+            stats.tip_credits_received =
+                stats.tip_credits_received.saturating_sub(credits[index].1);
+            self.author_stats.insert(&author, stats)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn remove_content(
+        &mut self,
+        cid: String,
+        reason: String,
+        author: Option<Owner>,
+    ) -> Result<String, StateError> {
+        let cid = self.original_cid(cid).await?;
+        let content = match self.contents.get(&cid).await? {
+            Some(content) => content,
+            _ => return Err(StateError::NotExist),
+        };
+        if let Some(author) = author {
+            if content.author != author {
+                return Err(StateError::NotAuthor);
+            }
+        }
+        if let Some(mut cids) = self.publishes.get(&content.author).await? {
+            cids.retain(|_cid| *_cid != cid);
+            self.publishes.insert(&content.author, cids)?;
+        }
+        if let Some(parent_cid) = content.comment_to_cid.clone() {
+            if let Some(mut comments) = self.content_comments.get(&parent_cid).await? {
+                let count = comments.len();
+                comments.retain(|_cid| *_cid != cid);
+                if comments.len() < count {
+                    if let Some(mut parent) = self.contents.get(&parent_cid).await? {
+                        parent.replies = parent.replies.saturating_sub(1);
+                        self.engage(&mut parent, -COMMENT_ENGAGEMENT);
+                        self.store_content(parent).await?;
+                    }
+                }
+                self.content_comments.insert(&parent_cid, comments)?;
+            }
+            if let Some(mut recommends) = self.content_recommends.get(&parent_cid).await? {
+                let count = recommends.len();
+                recommends.retain(|_cid| *_cid != cid);
+                if recommends.len() < count {
+                    self.engage_content(&parent_cid, -RECOMMEND_ENGAGEMENT)
+                        .await?;
+                }
+                self.content_recommends.insert(&parent_cid, recommends)?;
+            }
+        }
+        if content.comment_to_cid.is_none() {
+            self.unrank_content(&content).await?;
+            self.unindex_content(&cid, &content.title, &content.content)
+                .await?;
+        }
+        self.uncount_author_stats(&content).await?;
+        self.contents.remove(&cid)?;
+        self.removed_contents.insert(&cid, content.clone())?;
+        self.tombstones.insert(
+            &cid,
+            Tombstone {
+                cid: cid.clone(),
+                reason,
+                taken_down: author.is_none(),
+                removed_at: current_system_time(),
+            },
+        )?;
+        if let Some(series_id) = self.series_of_content.get(&cid).await? {
+            if let Some(mut series) = self.series.get(&series_id).await? {
+                series.cids.retain(|_cid| *_cid != cid);
+                self.series.insert(&series_id, series)?;
+            }
+            self.series_of_content.remove(&cid)?;
+        }
+        if author.is_none() {
+            if let Some(mut reports) = self.reports.get(&cid).await? {
+                reports.status = ReportStatus::Upheld;
+                self.reports.insert(&cid, reports)?;
+            }
+        }
+        Ok(cid)
+    }
+
+    pub(crate) async fn content_shares(&self, cid: String) -> Result<Vec<AuthorShare>, StateError> {
+        match self.contents.get(&cid).await? {
+            Some(content) => Ok(content.shares()),
+            _ => Err(StateError::InvalidContent),
+        }
+    }
+
+    pub(crate) async fn content_author(&self, cid: String) -> Result<Owner, StateError> {
+        match self.contents.get(&cid).await {
+            Ok(Some(content)) => Ok(content.author),
+            Ok(None) => Err(StateError::InvalidContent),
+            Err(err) => Err(StateError::ViewError(err)),
+        }
+    }
+}
+
+/// Attempts to debit from an account with insufficient funds.
+#[derive(Debug, Error)]
+#[error("Insufficient balance for transfer")]
+pub enum StateError {
+    #[error("Content already exists")]
+    AlreadyExists,
+
+    #[error("Invalid percent")]
+    InvalidPercent,
+
+    #[error("Content not exist")]
+    NotExist,
+
+    #[error("Too many actions within rate limit window")]
+    TooFrequently,
+
+    #[error("Already reacted to the content with the same kind")]
+    AlreadyReacted,
+
+    #[error("Not reacted to the content")]
+    NotReacted,
+
+    #[error("Invalid content")]
+    InvalidContent,
+
+    #[error("Only author can edit the content")]
+    NotAuthor,
+
+    #[error("Edit must be approved by review")]
+    EditRequiresReview,
+
+    #[error("Author cannot tip own content")]
+    InvalidTipper,
+
+    #[error("Invalid amount")]
+    InvalidAmount,
+
+    #[error("Content has none of the tags")]
+    TagMismatch,
+
+    #[error("Cannot follow self")]
+    InvalidFollower,
+
+    #[error("Already followed the author")]
+    AlreadyFollowed,
+
+    #[error("Not followed the author")]
+    NotFollowed,
+
+    #[error("Invalid profile")]
+    InvalidProfile,
+
+    #[error("Already bookmarked the content")]
+    AlreadyBookmarked,
+
+    #[error("Not bookmarked the content")]
+    NotBookmarked,
+
+    #[error("Invalid reading list")]
+    InvalidReadingList,
+
+    #[error("Reading list not exist")]
+    ReadingListNotExist,
+
+    #[error("Only owner can change the reading list")]
+    NotReadingListOwner,
+
+    #[error("Content already in the reading list")]
+    AlreadyInReadingList,
+
+    #[error("Content not in the reading list")]
+    NotInReadingList,
+
+    #[error("Invalid cursor")]
+    InvalidCursor,
+
+    #[error("Content is not premium")]
+    NotPremium,
+
+    #[error("Already unlocked the content")]
+    AlreadyUnlocked,
+
+    #[error("Author cannot report own content")]
+    InvalidReporter,
+
+    #[error("Already reported the content")]
+    AlreadyReported,
+
+    #[error("Reports of the content are closed")]
+    ReportsClosed,
+
+    #[error("Invalid series")]
+    InvalidSeries,
+
+    #[error("Series not exist")]
+    SeriesNotExist,
+
+    #[error("Only author can change the series")]
+    NotSeriesAuthor,
+
+    #[error("Content already in a series")]
+    AlreadyInSeries,
+
+    #[error("Content not in the series")]
+    NotInSeries,
+
+    #[error("Invalid author shares")]
+    InvalidShares,
+
+    #[error("Too many topics")]
+    TooManyTopics,
+
+    #[error("Body of {0} bytes exceeds limit of {1} bytes")]
+    BodyTooLarge(u64, u64),
+
+    #[error("Invalid content cid: {0}")]
+    InvalidCid(#[from] CidError),
 
     #[error("View error")]
     ViewError(#[from] linera_views::views::ViewError),
@@ -53,6 +53,35 @@ impl Contract for Foundation {
                     system_api::current_application_id().creation.chain_id,
                     Message::RequestSubscribe,
                 )),
+            Operation::Sponsor {
+                activity_id,
+                amount,
+            } => Ok(ExecutionResult::default().with_authenticated_message(
+                system_api::current_application_id().creation.chain_id,
+                Message::Sponsor {
+                    activity_id,
+                    amount,
+                },
+            )),
+            Operation::ClaimStipend => Ok(ExecutionResult::default().with_authenticated_message(
+                system_api::current_application_id().creation.chain_id,
+                Message::ClaimStipend,
+            )),
+            Operation::SetAuthorRewardCallers { application_ids } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::SetAuthorRewardCallers { application_ids },
+                )),
+            Operation::SetReviewCallers { application_ids } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::SetReviewCallers { application_ids },
+                )),
+            Operation::SetActivityCallers { application_ids } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::SetActivityCallers { application_ids },
+                )),
         }
     }
 
@@ -115,6 +144,73 @@ impl Contract for Foundation {
                     },
                 ))
             }
+            Message::Sponsor {
+                activity_id,
+                amount,
+            } => {
+                self.sponsor(context.authenticated_signer.unwrap(), activity_id, amount)
+                    .await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default().with_authenticated_message(
+                    dest,
+                    Message::Sponsor {
+                        activity_id,
+                        amount,
+                    },
+                ))
+            }
+            Message::OpenActivity { activity_id } => {
+                self.open_activity(activity_id)?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::OpenActivity { activity_id }))
+            }
+            Message::Refund { activity_id } => {
+                self.refund(activity_id).await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::Refund { activity_id }))
+            }
+            Message::RecordReviews {
+                object_id,
+                approved,
+                reviewers,
+                reviewer_number,
+            } => {
+                self.record_reviews(
+                    object_id.clone(),
+                    approved,
+                    reviewers.clone(),
+                    reviewer_number,
+                    system_api::current_system_time(),
+                )
+                .await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default().with_authenticated_message(
+                    dest,
+                    Message::RecordReviews {
+                        object_id,
+                        approved,
+                        reviewers,
+                        reviewer_number,
+                    },
+                ))
+            }
+            Message::ClaimStipend => {
+                self.claim_stipend(
+                    context.authenticated_signer.unwrap(),
+                    system_api::current_system_time(),
+                )
+                .await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::ClaimStipend))
+            }
             Message::Reward {
                 reward_user,
                 reward_type,
@@ -142,6 +238,64 @@ impl Contract for Foundation {
                     },
                 ))
             }
+            Message::RewardAuthors { object_id, shares } => {
+                self.reward_authors(object_id.clone(), shares.clone())
+                    .await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::RewardAuthors { object_id, shares }))
+            }
+            Message::Clawback { object_id } => {
+                self.clawback_authors(object_id.clone()).await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::Clawback { object_id }))
+            }
+            Message::SetAuthorRewardCallers { application_ids } => {
+                if context.message_id.chain_id
+                    != system_api::current_application_id().creation.chain_id
+                {
+                    return Err(ContractError::OperationNotAllowed);
+                }
+                self.set_author_reward_callers(application_ids.clone())
+                    .await;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default().with_authenticated_message(
+                    dest,
+                    Message::SetAuthorRewardCallers { application_ids },
+                ))
+            }
+            Message::SetReviewCallers { application_ids } => {
+                if context.message_id.chain_id
+                    != system_api::current_application_id().creation.chain_id
+                {
+                    return Err(ContractError::OperationNotAllowed);
+                }
+                self.set_review_callers(application_ids.clone()).await;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default().with_authenticated_message(
+                    dest,
+                    Message::SetReviewCallers { application_ids },
+                ))
+            }
+            Message::SetActivityCallers { application_ids } => {
+                if context.message_id.chain_id
+                    != system_api::current_application_id().creation.chain_id
+                {
+                    return Err(ContractError::OperationNotAllowed);
+                }
+                self.set_activity_callers(application_ids.clone()).await;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default().with_authenticated_message(
+                    dest,
+                    Message::SetActivityCallers { application_ids },
+                ))
+            }
             Message::Transfer { from, to, amount } => {
                 self.transfer(from, to, amount).await?;
                 let dest =
@@ -182,7 +336,7 @@ impl Contract for Foundation {
 
     async fn handle_application_call(
         &mut self,
-        _context: &CalleeContext,
+        context: &CalleeContext,
         call: Self::ApplicationCall,
         _forwarded_sessions: Vec<SessionId>,
     ) -> Result<ApplicationCallResult<Self::Message, Self::Response, Self::SessionState>, Self::Error>
@@ -220,6 +374,66 @@ impl Contract for Foundation {
                     system_api::current_application_id().creation.chain_id,
                     Message::Transfer { from, to, amount },
                 ),
+            ApplicationCall::OpenActivity { activity_id } => {
+                match context.authenticated_caller_id {
+                    Some(caller_id) if self.is_activity_caller(caller_id).await? => {}
+                    _ => return Err(ContractError::CallerNotAllowed),
+                }
+                ExecutionResult::default().with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::OpenActivity { activity_id },
+                )
+            }
+            ApplicationCall::Refund { activity_id } => {
+                match context.authenticated_caller_id {
+                    Some(caller_id) if self.is_activity_caller(caller_id).await? => {}
+                    _ => return Err(ContractError::CallerNotAllowed),
+                }
+                ExecutionResult::default().with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::Refund { activity_id },
+                )
+            }
+            ApplicationCall::RecordReviews {
+                object_id,
+                approved,
+                reviewers,
+                reviewer_number,
+            } => {
+                match context.authenticated_caller_id {
+                    Some(caller_id) if self.is_review_caller(caller_id).await? => {}
+                    _ => return Err(ContractError::CallerNotAllowed),
+                }
+                ExecutionResult::default().with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::RecordReviews {
+                        object_id,
+                        approved,
+                        reviewers,
+                        reviewer_number,
+                    },
+                )
+            }
+            ApplicationCall::Clawback { object_id } => {
+                match context.authenticated_caller_id {
+                    Some(caller_id) if self.is_author_reward_caller(caller_id).await? => {}
+                    _ => return Err(ContractError::CallerNotAllowed),
+                }
+                ExecutionResult::default().with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::Clawback { object_id },
+                )
+            }
+            ApplicationCall::RewardAuthors { object_id, shares } => {
+                match context.authenticated_caller_id {
+                    Some(caller_id) if self.is_author_reward_caller(caller_id).await? => {}
+                    _ => return Err(ContractError::CallerNotAllowed),
+                }
+                ExecutionResult::default().with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::RewardAuthors { object_id, shares },
+                )
+            }
             ApplicationCall::Balance { owner } => {
                 let balance = self.balance(owner).await?;
                 let mut result = ApplicationCallResult::default();
@@ -308,4 +522,10 @@ pub enum ContractError {
 
     #[error("Insufficient funds")]
     InsufficientFunds,
+
+    #[error("Caller not allowed")]
+    CallerNotAllowed,
+
+    #[error("Operation not allowed")]
+    OperationNotAllowed,
 }
@@ -1,7 +1,7 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use async_graphql::{Request, Response};
-use linera_sdk::base::{Amount, ContractAbi, Owner, ServiceAbi};
+use async_graphql::{Enum, Request, Response, SimpleObject};
+use linera_sdk::base::{Amount, ApplicationId, ContractAbi, Owner, ServiceAbi};
 use serde::{Deserialize, Serialize};
 
 pub struct FoundationAbi;
@@ -30,19 +30,55 @@ pub struct InitialState {
     pub author_reward_percent: u8,
     pub author_reward_factor: u8,
     pub activity_reward_percent: u8,
+    pub reviewer_stipend_percent: u8,
+    pub reviewer_stipend_epoch_ms: u64,
 }
 
-#[derive(Debug, Deserialize, Serialize, Copy, Clone)]
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Eq, PartialEq, Enum)]
 pub enum RewardType {
     Review,
     Publish,
     Activity,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject)]
+pub struct Pools {
+    pub foundation_balance: Amount,
+    pub review_reward_balance: Amount,
+    pub author_reward_balance: Amount,
+    pub activity_reward_balance: Amount,
+    /// activity_id, locked amount
+    pub activity_lock_funds: HashMap<u64, Amount>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, SimpleObject, Eq, PartialEq)]
+pub struct ReviewerStat {
+    pub reviewed: u64,
+    /// Reviews which are the same as the final decision
+    pub agreed: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, SimpleObject, Eq, PartialEq)]
+pub struct StipendEpoch {
+    pub epoch: u64,
+    pub decisions: u64,
+    pub reviewer_number: u16,
+    /// Object ids (cid or activity id) decided in this epoch
+    pub decided: HashSet<String>,
+    pub reviewers: HashMap<Owner, ReviewerStat>,
+    /// Fixed when the first stipend of the epoch is claimed
+    pub budget: Option<Amount>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub enum Operation {
     UserDeposit { amount: Amount },
     RequestSubscribe,
+    Sponsor { activity_id: u64, amount: Amount },
+    ClaimStipend,
+    SetAuthorRewardCallers { application_ids: Vec<ApplicationId> },
+    SetReviewCallers { application_ids: Vec<ApplicationId> },
+    SetActivityCallers { application_ids: Vec<ApplicationId> },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -78,6 +114,30 @@ pub enum ApplicationCall {
     Balance {
         owner: Owner,
     },
+    // Allow the created activity to be sponsored until it's refunded
+    OpenActivity {
+        activity_id: u64,
+    },
+    // Return unused sponsorship of rejected or finalized activity to sponsors
+    Refund {
+        activity_id: u64,
+    },
+    // Final decision of a review object, reviewers map to their approved flag
+    RecordReviews {
+        object_id: String,
+        approved: bool,
+        reviewers: HashMap<Owner, bool>,
+        reviewer_number: u16,
+    },
+    // Split author reward of co-authored content by percent of each author
+    RewardAuthors {
+        object_id: String,
+        shares: Vec<(Owner, u8)>,
+    },
+    // Take back the paid author reward of removed object to its reward pool
+    Clawback {
+        object_id: String,
+    },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -118,4 +178,37 @@ pub enum Message {
         activity_id: u64,
         amount: Amount,
     },
+    Sponsor {
+        activity_id: u64,
+        amount: Amount,
+    },
+    OpenActivity {
+        activity_id: u64,
+    },
+    Refund {
+        activity_id: u64,
+    },
+    RecordReviews {
+        object_id: String,
+        approved: bool,
+        reviewers: HashMap<Owner, bool>,
+        reviewer_number: u16,
+    },
+    ClaimStipend,
+    RewardAuthors {
+        object_id: String,
+        shares: Vec<(Owner, u8)>,
+    },
+    Clawback {
+        object_id: String,
+    },
+    SetAuthorRewardCallers {
+        application_ids: Vec<ApplicationId>,
+    },
+    SetReviewCallers {
+        application_ids: Vec<ApplicationId>,
+    },
+    SetActivityCallers {
+        application_ids: Vec<ApplicationId>,
+    },
 }
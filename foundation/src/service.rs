@@ -3,11 +3,12 @@
 mod state;
 
 use self::state::Foundation;
-use async_graphql::{EmptySubscription, Object, Request, Response, Schema};
+use async_graphql::{EmptySubscription, MergedObject, Object, Request, Response, Schema};
 use async_trait::async_trait;
-use foundation::Operation;
+use foundation::{Operation, Pools, RewardType};
 use linera_sdk::{
-    base::{Amount, WithServiceAbi},
+    base::{Amount, ApplicationId, Owner, WithServiceAbi},
+    service::system_api,
     QueryContext, Service, ViewStateStorage,
 };
 use std::sync::Arc;
@@ -29,12 +30,54 @@ impl Service for Foundation {
         _context: &QueryContext,
         request: Request,
     ) -> Result<Response, Self::Error> {
-        let schema = Schema::build(self.clone(), MutationRoot {}, EmptySubscription).finish();
+        let schema = Schema::build(
+            QueryRoot(
+                self.clone(),
+                Query {
+                    state: self.clone(),
+                },
+            ),
+            MutationRoot {},
+            EmptySubscription,
+        )
+        .finish();
         let response = schema.execute(request).await;
         Ok(response)
     }
 }
 
+struct Query {
+    state: Arc<Foundation>,
+}
+
+#[Object]
+impl Query {
+    async fn pools(&self) -> Result<Pools, async_graphql::Error> {
+        Ok(self.state.pools().await?)
+    }
+
+    async fn user_balance(&self, owner: Owner) -> Amount {
+        self.state.balance(owner).await.unwrap_or_default()
+    }
+
+    async fn claimable_stipend(&self, owner: Owner) -> Result<Amount, async_graphql::Error> {
+        Ok(self
+            .state
+            .claimable_stipend(owner, system_api::current_system_time())
+            .await?)
+    }
+
+    async fn projected_reward(
+        &self,
+        reward_type: RewardType,
+    ) -> Result<Amount, async_graphql::Error> {
+        Ok(self.state.projected_reward(reward_type)?)
+    }
+}
+
+#[derive(MergedObject)]
+struct QueryRoot(Arc<Foundation>, Query);
+
 struct MutationRoot;
 
 #[Object]
@@ -46,6 +89,30 @@ impl MutationRoot {
     async fn user_deposit(&self, amount: Amount) -> Vec<u8> {
         bcs::to_bytes(&Operation::UserDeposit { amount }).unwrap()
     }
+
+    async fn claim_stipend(&self) -> Vec<u8> {
+        bcs::to_bytes(&Operation::ClaimStipend).unwrap()
+    }
+
+    async fn set_author_reward_callers(&self, application_ids: Vec<ApplicationId>) -> Vec<u8> {
+        bcs::to_bytes(&Operation::SetAuthorRewardCallers { application_ids }).unwrap()
+    }
+
+    async fn set_review_callers(&self, application_ids: Vec<ApplicationId>) -> Vec<u8> {
+        bcs::to_bytes(&Operation::SetReviewCallers { application_ids }).unwrap()
+    }
+
+    async fn set_activity_callers(&self, application_ids: Vec<ApplicationId>) -> Vec<u8> {
+        bcs::to_bytes(&Operation::SetActivityCallers { application_ids }).unwrap()
+    }
+
+    async fn sponsor(&self, activity_id: u64, amount: Amount) -> Vec<u8> {
+        bcs::to_bytes(&Operation::Sponsor {
+            activity_id,
+            amount,
+        })
+        .unwrap()
+    }
 }
 
 /// An error that can occur while querying the service.
@@ -1,9 +1,9 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use foundation::{InitialState, RewardType};
+use foundation::{InitialState, Pools, ReviewerStat, RewardType, StipendEpoch};
 use linera_sdk::{
-    base::{Amount, ArithmeticError, Owner},
-    views::{MapView, RegisterView, ViewStorageContext},
+    base::{Amount, ApplicationId, ArithmeticError, Owner, Timestamp},
+    views::{MapView, RegisterView, SetView, ViewStorageContext},
 };
 use linera_views::views::{GraphQLView, RootView};
 use thiserror::Error;
@@ -22,6 +22,23 @@ pub struct Foundation {
     pub activity_reward_balance: RegisterView<Amount>,
     pub activity_lock_funds: MapView<u64, Amount>,
     pub user_balances: MapView<Owner, Amount>,
+    /// activity_id, sponsor, sponsored amount
+    pub activity_sponsors: MapView<u64, HashMap<Owner, Amount>>,
+    /// Activities which could be sponsored, closed when they're rejected or finalized
+    pub open_activities: SetView<u64>,
+    pub reviewer_stipend_percent: RegisterView<u8>,
+    pub reviewer_stipend_epoch_ms: RegisterView<u64>,
+    pub stipend_epochs: MapView<u64, StipendEpoch>,
+    /// Epochs in which reviewer has unclaimed stipend
+    pub reviewer_stipend_epochs: MapView<Owner, Vec<u64>>,
+    /// Paid author reward of each object, which may be clawed back when it's removed
+    pub author_rewards: MapView<String, HashMap<Owner, Amount>>,
+    /// Applications allowed to reward and clawback authors
+    pub author_reward_callers: SetView<ApplicationId>,
+    /// Applications allowed to record final decisions of reviews
+    pub review_callers: SetView<ApplicationId>,
+    /// Applications allowed to open and refund activities
+    pub activity_callers: SetView<ApplicationId>,
 }
 
 #[allow(dead_code)]
@@ -35,12 +52,19 @@ impl Foundation {
         {
             return Err(StateError::InvalidPercent);
         }
+        if state.reviewer_stipend_percent > 100 {
+            return Err(StateError::InvalidPercent);
+        }
         self.review_reward_percent.set(state.review_reward_percent);
         self.author_reward_percent.set(state.author_reward_percent);
         self.activity_reward_percent
             .set(state.activity_reward_percent);
         self.review_reward_factor.set(state.review_reward_factor);
         self.author_reward_factor.set(state.author_reward_factor);
+        self.reviewer_stipend_percent
+            .set(state.reviewer_stipend_percent);
+        self.reviewer_stipend_epoch_ms
+            .set(state.reviewer_stipend_epoch_ms);
         Ok(())
     }
 
@@ -51,6 +75,8 @@ impl Foundation {
             author_reward_percent: *self.author_reward_percent.get(),
             author_reward_factor: *self.author_reward_factor.get(),
             activity_reward_percent: *self.activity_reward_percent.get(),
+            reviewer_stipend_percent: *self.reviewer_stipend_percent.get(),
+            reviewer_stipend_epoch_ms: *self.reviewer_stipend_epoch_ms.get(),
         })
     }
 
@@ -166,7 +192,7 @@ impl Foundation {
             Some(balance) => balance,
             None => return Err(StateError::InsufficientBalance),
         };
-        let amount = Amount::from_tokens(50);
+        let amount = self.activity_reward_amount();
         if balance.le(&amount) {
             return Err(StateError::InsufficientBalance);
         }
@@ -177,28 +203,169 @@ impl Foundation {
         Ok(())
     }
 
-    pub(crate) async fn reward_author(&mut self, reward_user: Owner) -> Result<(), StateError> {
-        let balance = self.author_reward_balance.get().clone();
-        let amount = Amount::from_atto(
-            balance
+    pub(crate) fn activity_reward_amount(&self) -> Amount {
+        Amount::from_tokens(50)
+    }
+
+    pub(crate) fn author_reward_amount(&self) -> Result<Amount, StateError> {
+        Ok(Amount::from_atto(
+            self.author_reward_balance
+                .get()
                 .try_mul(*self.author_reward_factor.get() as u128)?
                 .saturating_div(Amount::from_atto(100)),
-        );
+        ))
+    }
+
+    pub(crate) fn review_reward_amount(&self) -> Result<Amount, StateError> {
+        Ok(Amount::from_atto(
+            self.review_reward_balance
+                .get()
+                .try_mul(*self.review_reward_factor.get() as u128)?
+                .saturating_div(Amount::from_atto(100)),
+        ))
+    }
+
+    // Amount the next reward of the type will get with current balance and factor
+    pub(crate) fn projected_reward(&self, reward_type: RewardType) -> Result<Amount, StateError> {
+        match reward_type {
+            RewardType::Activity => Ok(self.activity_reward_amount()),
+            RewardType::Publish => self.author_reward_amount(),
+            RewardType::Review => self.review_reward_amount(),
+        }
+    }
+
+    // Take back at most amount from user balance, reward user may already spent part of it
+    pub(crate) async fn clawback(
+        &mut self,
+        owner: Owner,
+        reward_type: RewardType,
+        amount: Amount,
+    ) -> Result<(), StateError> {
+        let balance = self.balance(owner).await?;
+        let amount = if balance.lt(&amount) { balance } else { amount };
+        if amount == Amount::ZERO {
+            return Ok(());
+        }
+        self.user_balances
+            .insert(&owner, balance.saturating_sub(amount))?;
+        match reward_type {
+            RewardType::Activity => self
+                .activity_reward_balance
+                .set(self.activity_reward_balance.get().try_add(amount)?),
+            RewardType::Publish => self
+                .author_reward_balance
+                .set(self.author_reward_balance.get().try_add(amount)?),
+            RewardType::Review => self
+                .review_reward_balance
+                .set(self.review_reward_balance.get().try_add(amount)?),
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn set_author_reward_callers(&mut self, application_ids: Vec<ApplicationId>) {
+        application_ids
+            .iter()
+            .for_each(|application_id| self.author_reward_callers.insert(application_id).unwrap())
+    }
+
+    pub(crate) async fn is_author_reward_caller(
+        &self,
+        application_id: ApplicationId,
+    ) -> Result<bool, StateError> {
+        Ok(self.author_reward_callers.contains(&application_id).await?)
+    }
+
+    pub(crate) async fn set_review_callers(&mut self, application_ids: Vec<ApplicationId>) {
+        application_ids
+            .iter()
+            .for_each(|application_id| self.review_callers.insert(application_id).unwrap())
+    }
+
+    pub(crate) async fn is_review_caller(
+        &self,
+        application_id: ApplicationId,
+    ) -> Result<bool, StateError> {
+        Ok(self.review_callers.contains(&application_id).await?)
+    }
+
+    pub(crate) async fn set_activity_callers(&mut self, application_ids: Vec<ApplicationId>) {
+        application_ids
+            .iter()
+            .for_each(|application_id| self.activity_callers.insert(application_id).unwrap())
+    }
+
+    pub(crate) async fn is_activity_caller(
+        &self,
+        application_id: ApplicationId,
+    ) -> Result<bool, StateError> {
+        Ok(self.activity_callers.contains(&application_id).await?)
+    }
+
+    // Take back the recorded author reward of the object from each of its authors
+    pub(crate) async fn clawback_authors(&mut self, object_id: String) -> Result<(), StateError> {
+        let rewards = match self.author_rewards.get(&object_id).await? {
+            Some(rewards) => rewards,
+            _ => return Ok(()),
+        };
+        for (author, amount) in rewards {
+            self.clawback(author, RewardType::Publish, amount).await?;
+        }
+        self.author_rewards.remove(&object_id)?;
+        Ok(())
+    }
+
+    pub(crate) async fn reward_author(&mut self, reward_user: Owner) -> Result<(), StateError> {
+        let balance = self.author_reward_balance.get().clone();
+        let amount = self.author_reward_amount()?;
         self.reward_user(reward_user, amount).await?;
         self.author_reward_balance
             .set(balance.saturating_sub(amount));
         Ok(())
     }
 
+    // Rounding dust of the split is kept in the pool, an object is only rewarded once
+    pub(crate) async fn reward_authors(
+        &mut self,
+        object_id: String,
+        shares: Vec<(Owner, u8)>,
+    ) -> Result<(), StateError> {
+        if self.author_rewards.get(&object_id).await?.is_some() {
+            return Err(StateError::AlreadyRewarded);
+        }
+        if shares
+            .iter()
+            .map(|(_, percent)| *percent as u32)
+            .sum::<u32>()
+            > 100
+        {
+            return Err(StateError::InvalidPercent);
+        }
+        let amount = self.author_reward_amount()?;
+        let mut rewarded = Amount::ZERO;
+        let mut rewards = HashMap::new();
+        for (author, percent) in shares {
+            let share = Amount::from_atto(
+                amount
+                    .saturating_mul(percent as u128)
+                    .saturating_div(Amount::from_atto(100)),
+            );
+            self.reward_user(author, share).await?;
+            rewarded = rewarded.try_add(share)?;
+            let paid = rewards.entry(author).or_insert(Amount::ZERO);
+            *paid = paid.try_add(share)?;
+        }
+        self.author_reward_balance
+            .set(self.author_reward_balance.get().saturating_sub(rewarded));
+        self.author_rewards.insert(&object_id, rewards)?;
+        Ok(())
+    }
+
     pub(crate) async fn reward_reviewer(&mut self, reward_user: Owner) -> Result<(), StateError> {
         let balance = self.review_reward_balance.get().clone();
-        let amount = balance
-            .try_mul(*self.review_reward_factor.get() as u128)?
-            .saturating_div(Amount::from_atto(100));
-        self.reward_user(reward_user, Amount::from_atto(amount))
-            .await?;
+        let amount = self.review_reward_amount()?;
+        self.reward_user(reward_user, amount).await?;
         self.review_reward_balance
-            .set(balance.saturating_sub(Amount::from_atto(amount)));
+            .set(balance.saturating_sub(amount));
         Ok(())
     }
 
@@ -235,13 +402,269 @@ impl Foundation {
         Ok(())
     }
 
+    pub(crate) fn open_activity(&mut self, activity_id: u64) -> Result<(), StateError> {
+        self.open_activities.insert(&activity_id)?;
+        Ok(())
+    }
+
+    pub(crate) async fn sponsor(
+        &mut self,
+        sponsor: Owner,
+        activity_id: u64,
+        amount: Amount,
+    ) -> Result<(), StateError> {
+        if !self.open_activities.contains(&activity_id).await? {
+            return Err(StateError::ActivityNotOpen);
+        }
+        let balance = self.balance(sponsor).await?;
+        if balance.lt(&amount) {
+            return Err(StateError::InsufficientBalance);
+        }
+        self.user_balances
+            .insert(&sponsor, balance.saturating_sub(amount))?;
+        self.lock(activity_id, amount).await?;
+        let mut sponsors = self
+            .activity_sponsors
+            .get(&activity_id)
+            .await?
+            .unwrap_or_default();
+        let sponsored = match sponsors.get(&sponsor) {
+            Some(sponsored) => sponsored.try_add(amount)?,
+            None => amount,
+        };
+        sponsors.insert(sponsor, sponsored);
+        self.activity_sponsors.insert(&activity_id, sponsors)?;
+        Ok(())
+    }
+
+    // Refund what is left in the activity funds to sponsors according to their contribution
+    // Funds which are not sponsored (e.g. approved budget) are kept locked
+    pub(crate) async fn refund(&mut self, activity_id: u64) -> Result<(), StateError> {
+        self.open_activities.remove(&activity_id)?;
+        let sponsors = match self.activity_sponsors.get(&activity_id).await? {
+            Some(sponsors) => sponsors,
+            None => return Ok(()),
+        };
+        let funds = self
+            .activity_lock_funds
+            .get(&activity_id)
+            .await?
+            .unwrap_or_default();
+        let total: Amount = sponsors.values().sum();
+        let refundable = if funds.lt(&total) { funds } else { total };
+        if total == Amount::ZERO || refundable == Amount::ZERO {
+            self.activity_sponsors.remove(&activity_id)?;
+            return Ok(());
+        }
+        // Parts per million of sponsored amount to be refunded
+        let ratio = refundable.saturating_mul(1_000_000).saturating_div(total);
+        let mut refunded = Amount::ZERO;
+        for (sponsor, sponsored) in sponsors.into_iter() {
+            let amount = Amount::from_atto(
+                sponsored
+                    .saturating_mul(ratio)
+                    .saturating_div(Amount::from_atto(1_000_000)),
+            );
+            if amount == Amount::ZERO {
+                continue;
+            }
+            self.reward_user(sponsor, amount).await?;
+            refunded = refunded.try_add(amount)?;
+        }
+        self.activity_lock_funds
+            .insert(&activity_id, funds.saturating_sub(refunded))?;
+        self.activity_sponsors.remove(&activity_id)?;
+        Ok(())
+    }
+
+    pub(crate) fn stipend_enabled(&self) -> bool {
+        *self.reviewer_stipend_percent.get() > 0 && *self.reviewer_stipend_epoch_ms.get() > 0
+    }
+
+    pub(crate) fn current_stipend_epoch(&self, now: Timestamp) -> u64 {
+        now.micros() / self.reviewer_stipend_epoch_ms.get().saturating_mul(1000)
+    }
+
+    // Record reviews of a decided object to the current stipend epoch
+    pub(crate) async fn record_reviews(
+        &mut self,
+        object_id: String,
+        approved: bool,
+        reviewers: HashMap<Owner, bool>,
+        reviewer_number: u16,
+        now: Timestamp,
+    ) -> Result<(), StateError> {
+        if !self.stipend_enabled() {
+            return Ok(());
+        }
+        let epoch = self.current_stipend_epoch(now);
+        let mut stipend_epoch = match self.stipend_epochs.get(&epoch).await? {
+            Some(stipend_epoch) => stipend_epoch,
+            None => StipendEpoch {
+                epoch,
+                ..StipendEpoch::default()
+            },
+        };
+        if !stipend_epoch.decided.insert(object_id) {
+            return Ok(());
+        }
+        stipend_epoch.decisions += 1;
+        if reviewer_number > stipend_epoch.reviewer_number {
+            stipend_epoch.reviewer_number = reviewer_number;
+        }
+        for (reviewer, reviewer_approved) in reviewers.into_iter() {
+            let stat = stipend_epoch
+                .reviewers
+                .entry(reviewer)
+                .or_insert(ReviewerStat::default());
+            stat.reviewed += 1;
+            if reviewer_approved == approved {
+                stat.agreed += 1;
+            }
+            let mut epochs = self
+                .reviewer_stipend_epochs
+                .get(&reviewer)
+                .await?
+                .unwrap_or_default();
+            if !epochs.contains(&epoch) {
+                epochs.push(epoch);
+                self.reviewer_stipend_epochs.insert(&reviewer, epochs)?;
+            }
+        }
+        self.stipend_epochs.insert(&epoch, stipend_epoch)?;
+        Ok(())
+    }
+
+    fn stipend_budget(&self, stipend_epoch: &StipendEpoch) -> Amount {
+        match stipend_epoch.budget {
+            Some(budget) => budget,
+            None => Amount::from_atto(
+                self.review_reward_balance
+                    .get()
+                    .saturating_mul(*self.reviewer_stipend_percent.get() as u128)
+                    .saturating_div(Amount::from_atto(100)),
+            ),
+        }
+    }
+
+    // Each reviewer could get at most budget / reviewer_number in an epoch
+    // The amount is scaled by participation rate and agreement rate (in parts per million)
+    fn stipend_of_epoch(&self, reviewer: Owner, stipend_epoch: &StipendEpoch) -> Amount {
+        let stat = match stipend_epoch.reviewers.get(&reviewer) {
+            Some(stat) => stat,
+            None => return Amount::ZERO,
+        };
+        if stat.reviewed == 0 || stipend_epoch.decisions == 0 || stipend_epoch.reviewer_number == 0
+        {
+            return Amount::ZERO;
+        }
+        let participation =
+            (stat.reviewed as u128 * 1_000_000 / stipend_epoch.decisions as u128).min(1_000_000);
+        let agreement = stat.agreed as u128 * 1_000_000 / stat.reviewed as u128;
+        let base = self
+            .stipend_budget(stipend_epoch)
+            .saturating_div(Amount::from_atto(stipend_epoch.reviewer_number as u128));
+        Amount::from_atto(
+            Amount::from_atto(base)
+                .saturating_mul(participation * agreement / 1_000_000)
+                .saturating_div(Amount::from_atto(1_000_000)),
+        )
+    }
+
+    pub(crate) async fn claimable_stipend(
+        &self,
+        reviewer: Owner,
+        now: Timestamp,
+    ) -> Result<Amount, StateError> {
+        if !self.stipend_enabled() {
+            return Ok(Amount::ZERO);
+        }
+        let current_epoch = self.current_stipend_epoch(now);
+        let mut amount = Amount::ZERO;
+        for epoch in self
+            .reviewer_stipend_epochs
+            .get(&reviewer)
+            .await?
+            .unwrap_or_default()
+        {
+            if epoch >= current_epoch {
+                continue;
+            }
+            if let Some(stipend_epoch) = self.stipend_epochs.get(&epoch).await? {
+                amount = amount.saturating_add(self.stipend_of_epoch(reviewer, &stipend_epoch));
+            }
+        }
+        Ok(amount)
+    }
+
+    // Pay stipend of all finished epochs to reviewer from review reward balance
+    pub(crate) async fn claim_stipend(
+        &mut self,
+        reviewer: Owner,
+        now: Timestamp,
+    ) -> Result<Amount, StateError> {
+        if !self.stipend_enabled() {
+            return Err(StateError::StipendNotEnabled);
+        }
+        let current_epoch = self.current_stipend_epoch(now);
+        let epochs = self
+            .reviewer_stipend_epochs
+            .get(&reviewer)
+            .await?
+            .unwrap_or_default();
+        let mut unclaimed = Vec::new();
+        let mut claimed = Amount::ZERO;
+        let mut finished = 0;
+        for epoch in epochs {
+            if epoch >= current_epoch {
+                unclaimed.push(epoch);
+                continue;
+            }
+            finished += 1;
+            let mut stipend_epoch = match self.stipend_epochs.get(&epoch).await? {
+                Some(stipend_epoch) => stipend_epoch,
+                None => continue,
+            };
+            if stipend_epoch.budget.is_none() {
+                stipend_epoch.budget = Some(self.stipend_budget(&stipend_epoch));
+            }
+            let balance = *self.review_reward_balance.get();
+            let mut amount = self.stipend_of_epoch(reviewer, &stipend_epoch);
+            if balance.lt(&amount) {
+                amount = balance;
+            }
+            self.review_reward_balance
+                .set(balance.saturating_sub(amount));
+            claimed = claimed.try_add(amount)?;
+            stipend_epoch.reviewers.remove(&reviewer);
+            self.stipend_epochs.insert(&epoch, stipend_epoch)?;
+        }
+        if finished == 0 {
+            return Err(StateError::NothingToClaim);
+        }
+        self.reward_user(reviewer, claimed).await?;
+        self.reviewer_stipend_epochs.insert(&reviewer, unclaimed)?;
+        Ok(claimed)
+    }
+
     pub(crate) async fn balance(&self, owner: Owner) -> Result<Amount, StateError> {
-        Ok(self
-            .user_balances
-            .get(&owner)
-            .await
-            .unwrap()
-            .unwrap_or_default())
+        Ok(self.user_balances.get(&owner).await?.unwrap_or_default())
+    }
+
+    pub(crate) async fn pools(&self) -> Result<Pools, StateError> {
+        let mut activity_lock_funds = HashMap::new();
+        for activity_id in self.activity_lock_funds.indices().await? {
+            if let Some(amount) = self.activity_lock_funds.get(&activity_id).await? {
+                activity_lock_funds.insert(activity_id, amount);
+            }
+        }
+        Ok(Pools {
+            foundation_balance: *self.foundation_balance.get(),
+            review_reward_balance: *self.review_reward_balance.get(),
+            author_reward_balance: *self.author_reward_balance.get(),
+            activity_reward_balance: *self.activity_reward_balance.get(),
+            activity_lock_funds,
+        })
     }
 
     pub(crate) async fn spend_activity_funds(
@@ -337,4 +760,16 @@ pub enum StateError {
 
     #[error("Invalid activity funds")]
     InvalidActivityFunds,
+
+    #[error("Reviewer stipend not enabled")]
+    StipendNotEnabled,
+
+    #[error("Nothing to claim")]
+    NothingToClaim,
+
+    #[error("Already rewarded")]
+    AlreadyRewarded,
+
+    #[error("Activity not open")]
+    ActivityNotOpen,
 }
@@ -365,6 +365,15 @@ impl Contract for Market {
                 );
                 Ok(result)
             }
+            ApplicationCall::ValidateTokenOwner {
+                owner,
+                collection_id,
+                token_id,
+            } => {
+                self.validate_token_owner(owner, collection_id, token_id)
+                    .await?;
+                Ok(ApplicationCallResult::default())
+            }
         }
     }
 
@@ -107,6 +107,12 @@ pub enum ApplicationCall {
         uris: Vec<String>,
         publisher: Owner,
     },
+    /// Fail if owner doesn't own the NFT
+    ValidateTokenOwner {
+        owner: Owner,
+        collection_id: u64,
+        token_id: u16,
+    },
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -390,6 +390,18 @@ impl Market {
         }
     }
 
+    pub(crate) async fn validate_token_owner(
+        &self,
+        owner: Owner,
+        collection_id: u64,
+        token_id: u16,
+    ) -> Result<(), StateError> {
+        match self.nft_owner(collection_id, token_id).await {
+            Ok(_owner) if _owner == owner => Ok(()),
+            _ => Err(StateError::NotTokenOwner),
+        }
+    }
+
     pub(crate) async fn trading_fee(&self, amount: Amount) -> Result<Amount, StateError> {
         Ok(Amount::from_atto(
             Amount::from_atto(*self.trade_fee_percent.get() as u128)
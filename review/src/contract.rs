@@ -7,11 +7,12 @@ use std::collections::HashMap;
 use self::state::Review;
 use async_trait::async_trait;
 use credit::CreditAbi;
-use feed::FeedAbi;
+use feed::{AuthorShare, FeedAbi};
 use foundation::FoundationAbi;
 use linera_sdk::{
     base::{
-        Amount, ApplicationId, ChainId, ChannelName, Destination, Owner, SessionId, WithContractAbi,
+        Amount, ApplicationId, ChainId, ChannelName, Destination, Owner, SessionId, Timestamp,
+        WithContractAbi,
     },
     contract::system_api,
     ApplicationCallResult, CalleeContext, Contract, ExecutionResult, MessageContext,
@@ -19,7 +20,7 @@ use linera_sdk::{
 };
 // use linera_views::views::ViewError;
 use market::MarketAbi;
-use review::{ApplicationCall, Asset, Content, InitialState, Message, Operation};
+use review::{ApplicationCall, Asset, Content, InitialState, Message, Operation, ScheduledContent};
 use thiserror::Error;
 
 linera_sdk::contract!(Review);
@@ -78,12 +79,18 @@ impl Contract for Review {
                 cid,
                 title,
                 content,
+                tags,
+                authors,
+                publish_at,
             } => Ok(ExecutionResult::default().with_authenticated_message(
                 system_api::current_application_id().creation.chain_id,
                 Message::SubmitContent {
                     cid,
                     title,
                     content,
+                    tags,
+                    authors,
+                    publish_at,
                 },
             )),
             Operation::ApproveContent {
@@ -108,6 +115,16 @@ impl Contract for Review {
                     reason,
                 },
             )),
+            Operation::TakedownContent { cid, reason } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::TakedownContent { cid, reason },
+                )),
+            Operation::RejectTakedown { cid, reason } => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::RejectTakedown { cid, reason },
+                )),
             Operation::SubmitComment {
                 cid,
                 comment_cid,
@@ -120,6 +137,20 @@ impl Contract for Review {
                     comment,
                 },
             )),
+            Operation::SubmitEdit {
+                cid,
+                new_cid,
+                title,
+                content,
+            } => Ok(ExecutionResult::default().with_authenticated_message(
+                system_api::current_application_id().creation.chain_id,
+                Message::SubmitEdit {
+                    cid,
+                    new_cid,
+                    title,
+                    content,
+                },
+            )),
             Operation::ApproveAsset { cid, reason } => Ok(ExecutionResult::default()
                 .with_authenticated_message(
                     system_api::current_application_id().creation.chain_id,
@@ -146,6 +177,11 @@ impl Contract for Review {
                     name,
                 },
             )),
+            Operation::ReleaseScheduled => Ok(ExecutionResult::default()
+                .with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::ReleaseScheduled,
+                )),
             Operation::RequestSubscribe => Ok(ExecutionResult::default()
                 .with_authenticated_message(
                     system_api::current_application_id().creation.chain_id,
@@ -245,13 +281,19 @@ impl Contract for Review {
                 cid,
                 title,
                 content,
+                tags,
+                authors,
+                publish_at,
             } => {
                 let author = context.authenticated_signer.unwrap();
                 self._submit_content(
                     cid.clone(),
                     title.clone(),
                     content.clone(),
+                    tags.clone(),
                     author,
+                    authors.clone(),
+                    publish_at,
                     context.chain_id == system_api::current_application_id().creation.chain_id,
                 )
                 .await?;
@@ -263,6 +305,9 @@ impl Contract for Review {
                         cid,
                         title,
                         content,
+                        tags,
+                        authors,
+                        publish_at,
                     },
                 ))
             }
@@ -313,6 +358,41 @@ impl Contract for Review {
                     },
                 ))
             }
+            Message::TakedownContent { cid, reason } => {
+                self._takedown_content(
+                    context.authenticated_signer.unwrap(),
+                    cid.clone(),
+                    reason.clone(),
+                    true,
+                    context.chain_id == system_api::current_application_id().creation.chain_id,
+                )
+                .await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::TakedownContent { cid, reason }))
+            }
+            Message::OpenTakedown { cid, reasons } => {
+                self.open_takedown(cid.clone(), reasons.clone()).await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::OpenTakedown { cid, reasons }))
+            }
+            Message::RejectTakedown { cid, reason } => {
+                self._takedown_content(
+                    context.authenticated_signer.unwrap(),
+                    cid.clone(),
+                    reason.clone(),
+                    false,
+                    context.chain_id == system_api::current_application_id().creation.chain_id,
+                )
+                .await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::RejectTakedown { cid, reason }))
+            }
             Message::SubmitComment {
                 cid,
                 comment_cid,
@@ -338,6 +418,34 @@ impl Contract for Review {
                     },
                 ))
             }
+            Message::SubmitEdit {
+                cid,
+                new_cid,
+                title,
+                content,
+            } => {
+                let author = context.authenticated_signer.unwrap();
+                self._submit_edit(
+                    cid.clone(),
+                    new_cid.clone(),
+                    title.clone(),
+                    content.clone(),
+                    author,
+                    context.chain_id == system_api::current_application_id().creation.chain_id,
+                )
+                .await?;
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default().with_authenticated_message(
+                    dest,
+                    Message::SubmitEdit {
+                        cid,
+                        new_cid,
+                        title,
+                        content,
+                    },
+                ))
+            }
             Message::ApproveAsset { cid, reason } => {
                 self._approve_asset(
                     context.authenticated_signer.unwrap(),
@@ -393,6 +501,20 @@ impl Contract for Review {
                     },
                 ))
             }
+            Message::ReleaseScheduled => {
+                let cids = self._release_scheduled().await?;
+                if cids.is_empty() {
+                    return Ok(ExecutionResult::default());
+                }
+                let dest =
+                    Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+                Ok(ExecutionResult::default()
+                    .with_authenticated_message(dest, Message::ScheduledReleased { cids }))
+            }
+            Message::ScheduledReleased { cids } => {
+                self.remove_scheduled(cids).await?;
+                Ok(ExecutionResult::default())
+            }
             Message::RequestSubscribe => {
                 let mut result = ExecutionResult::default();
                 if context.message_id.chain_id
@@ -489,16 +611,30 @@ impl Contract for Review {
 
     async fn handle_application_call(
         &mut self,
-        _context: &CalleeContext,
+        context: &CalleeContext,
         call: Self::ApplicationCall,
         _forwarded_sessions: Vec<SessionId>,
     ) -> Result<ApplicationCallResult<Self::Message, Self::Response, Self::SessionState>, Self::Error>
     {
         match call {
+            ApplicationCall::OpenTakedown { cid, reasons } => {
+                if context.authenticated_caller_id != Some(Self::feed_app_id()?.forget_abi()) {
+                    return Err(ContractError::InvalidCaller);
+                }
+                let mut result = ApplicationCallResult::default();
+                result.execution_result = ExecutionResult::default().with_authenticated_message(
+                    system_api::current_application_id().creation.chain_id,
+                    Message::OpenTakedown { cid, reasons },
+                );
+                Ok(result)
+            }
             ApplicationCall::SubmitContent {
                 cid,
                 title,
                 content,
+                tags,
+                authors,
+                publish_at,
             } => {
                 let mut result = ApplicationCallResult::default();
                 result.execution_result = ExecutionResult::default().with_authenticated_message(
@@ -507,6 +643,9 @@ impl Contract for Review {
                         cid,
                         title,
                         content,
+                        tags,
+                        authors,
+                        publish_at,
                     },
                 );
                 Ok(result)
@@ -587,13 +726,17 @@ impl Review {
         cid: String,
         title: String,
         content: String,
+        tags: Vec<String>,
         author: Owner,
+        authors: Vec<AuthorShare>,
     ) -> Result<(), ContractError> {
         let call = feed::ApplicationCall::Publish {
             cid: cid.clone(),
             title,
             content,
             author,
+            tags,
+            authors,
         };
         self.call_application(true, Self::feed_app_id()?, &call, vec![])
             .await?;
@@ -605,11 +748,13 @@ impl Review {
         cid: String,
         reason_cid: String,
         reason: String,
+        recommender: Owner,
     ) -> Result<(), ContractError> {
         let call = feed::ApplicationCall::Recommend {
             cid: cid.clone(),
             reason_cid: reason_cid.clone(),
             reason,
+            recommender,
         };
         self.call_application(true, Self::feed_app_id()?, &call, vec![])
             .await?;
@@ -634,6 +779,63 @@ impl Review {
         Ok(())
     }
 
+    async fn rate_limit(
+        &mut self,
+        owner: Owner,
+        action: feed::Action,
+    ) -> Result<(), ContractError> {
+        let call = feed::ApplicationCall::RateLimit { owner, action };
+        self.call_application(true, Self::feed_app_id()?, &call, vec![])
+            .await?;
+        Ok(())
+    }
+
+    async fn edit_content(
+        &mut self,
+        cid: String,
+        new_cid: String,
+        title: String,
+        content: String,
+        author: Owner,
+    ) -> Result<(), ContractError> {
+        let call = feed::ApplicationCall::Edit {
+            cid,
+            new_cid,
+            title,
+            content,
+            author,
+        };
+        self.call_application(true, Self::feed_app_id()?, &call, vec![])
+            .await?;
+        Ok(())
+    }
+
+    async fn takedown_feed_content(
+        &mut self,
+        cid: String,
+        reason: String,
+    ) -> Result<(), ContractError> {
+        let call = feed::ApplicationCall::Takedown { cid, reason };
+        self.call_application(true, Self::feed_app_id()?, &call, vec![])
+            .await?;
+        Ok(())
+    }
+
+    async fn dismiss_feed_reports(&mut self, cid: String) -> Result<(), ContractError> {
+        let call = feed::ApplicationCall::DismissReports { cid };
+        self.call_application(true, Self::feed_app_id()?, &call, vec![])
+            .await?;
+        Ok(())
+    }
+
+    async fn content_author(&mut self, cid: String) -> Result<Option<Owner>, ContractError> {
+        let call = feed::ApplicationCall::ContentAuthor { cid };
+        let (author, _) = self
+            .call_application(true, Self::feed_app_id()?, &call, vec![])
+            .await?;
+        Ok(author)
+    }
+
     async fn create_collection(
         &mut self,
         base_uri: String,
@@ -668,6 +870,33 @@ impl Review {
         Ok(())
     }
 
+    async fn refund_activity_funds(&mut self, activity_id: u64) -> Result<(), ContractError> {
+        let call = foundation::ApplicationCall::Refund { activity_id };
+        self.call_application(true, Self::foundation_app_id()?, &call, vec![])
+            .await?;
+        Ok(())
+    }
+
+    async fn record_reviews(
+        &mut self,
+        object_id: String,
+        approved: bool,
+        reviewers: HashMap<Owner, review::Review>,
+    ) -> Result<(), ContractError> {
+        let call = foundation::ApplicationCall::RecordReviews {
+            object_id,
+            approved,
+            reviewers: reviewers
+                .into_iter()
+                .map(|(reviewer, review)| (reviewer, review.approved))
+                .collect(),
+            reviewer_number: *self.reviewer_number.get(),
+        };
+        self.call_application(true, Self::foundation_app_id()?, &call, vec![])
+            .await?;
+        Ok(())
+    }
+
     async fn _initialize(&mut self, state: InitialState) -> Result<(), ContractError> {
         self.initialize_review(state).await?;
         Ok(())
@@ -751,20 +980,30 @@ impl Review {
         cid: String,
         title: String,
         content: String,
+        tags: Vec<String>,
         author: Owner,
+        authors: Vec<AuthorShare>,
+        publish_at: Option<Timestamp>,
         creation_chain: bool,
     ) -> Result<(), ContractError> {
+        if creation_chain {
+            self.rate_limit(author, feed::Action::Publish).await?;
+        }
         self.submit_content(Content {
             // TODO: notify author
             cid,
             comment_to_cid: None,
+            edit_of: None,
             title,
             content,
+            tags,
             author,
+            authors,
             reviewers: HashMap::default(),
             approved: 0,
             rejected: 0,
             created_at: system_api::current_system_time(),
+            publish_at,
         })
         .await?;
         if !creation_chain {
@@ -782,16 +1021,23 @@ impl Review {
         author: Owner,
         creation_chain: bool,
     ) -> Result<(), ContractError> {
+        if creation_chain {
+            self.rate_limit(author, feed::Action::Comment).await?;
+        }
         self.submit_content(Content {
             cid,
             comment_to_cid: Some(comment_to_cid),
+            edit_of: None,
             title: String::default(),
             content: comment,
+            tags: Vec::new(),
             author,
+            authors: Vec::new(),
             reviewers: HashMap::default(),
             approved: 0,
             rejected: 0,
             created_at: system_api::current_system_time(),
+            publish_at: None,
         })
         .await?;
         if !creation_chain {
@@ -801,6 +1047,65 @@ impl Review {
         Ok(())
     }
 
+    async fn _takedown_content(
+        &mut self,
+        reviewer: Owner,
+        cid: String,
+        reason: String,
+        approved: bool,
+        creation_chain: bool,
+    ) -> Result<(), ContractError> {
+        if creation_chain && self.content_author(cid.clone()).await?.is_none() {
+            return Err(ContractError::InvalidContent);
+        }
+        let takedown = self
+            .vote_takedown(reviewer, cid.clone(), reason.clone(), approved)
+            .await?;
+        if !creation_chain {
+            return Ok(());
+        }
+        if let Some(takedown) = takedown {
+            self.record_reviews(cid.clone(), takedown.taken_down, takedown.reviewers)
+                .await?;
+            match takedown.taken_down {
+                true => self.takedown_feed_content(cid, reason).await?,
+                false => self.dismiss_feed_reports(cid).await?,
+            }
+        }
+        Ok(())
+    }
+
+    async fn _submit_edit(
+        &mut self,
+        cid: String,
+        new_cid: String,
+        title: String,
+        content: String,
+        author: Owner,
+        creation_chain: bool,
+    ) -> Result<(), ContractError> {
+        if creation_chain && self.content_author(cid.clone()).await? != Some(author) {
+            return Err(ContractError::InvalidUser);
+        }
+        self.submit_content(Content {
+            cid: new_cid,
+            comment_to_cid: None,
+            edit_of: Some(cid),
+            title,
+            content,
+            tags: Vec::new(),
+            author,
+            authors: Vec::new(),
+            reviewers: HashMap::default(),
+            approved: 0,
+            rejected: 0,
+            created_at: system_api::current_system_time(),
+            publish_at: None,
+        })
+        .await?;
+        Ok(())
+    }
+
     async fn _approve_content(
         &mut self,
         reviewer: Owner,
@@ -816,13 +1121,37 @@ impl Review {
                 reason.clone().unwrap_or_default(),
             )
             .await?;
+        let scheduled = match &content {
+            Some(content) => {
+                self.schedule_if_pending(
+                    content.clone(),
+                    reviewer,
+                    reason_cid.clone(),
+                    reason.clone(),
+                )
+                .await?
+            }
+            _ => false,
+        };
         if !creation_chain {
             return Ok(());
         }
         match content {
             Some(content) => {
-                match content.comment_to_cid {
-                    Some(comment_to_cid) => {
+                self.record_reviews(content.cid.clone(), true, content.reviewers.clone())
+                    .await?;
+                match (content.edit_of, content.comment_to_cid) {
+                    (Some(edit_of), _) => {
+                        self.edit_content(
+                            edit_of,
+                            content.cid.clone(),
+                            content.title,
+                            content.content,
+                            content.author,
+                        )
+                        .await?;
+                    }
+                    (_, Some(comment_to_cid)) => {
                         self.comment_content(
                             comment_to_cid,
                             content.cid.clone(),
@@ -831,20 +1160,29 @@ impl Review {
                         )
                         .await?;
                     }
+                    _ if scheduled => {}
                     _ => {
                         self.publish_content(
                             content.cid,
                             content.title,
                             content.content,
+                            content.tags,
                             content.author,
+                            content.authors,
                         )
                         .await?
                     }
                 }
+                // Recommendation of scheduled content is sent when it's released
                 match reason_cid {
-                    Some(cid) => {
-                        self.recommend_content(content_cid.clone(), cid, reason.unwrap_or_default())
-                            .await?
+                    Some(cid) if !scheduled => {
+                        self.recommend_content(
+                            content_cid.clone(),
+                            cid,
+                            reason.unwrap_or_default(),
+                            reviewer,
+                        )
+                        .await?
                     }
                     _ => {}
                 }
@@ -860,6 +1198,65 @@ impl Review {
         Ok(())
     }
 
+    // New publications approved before their publish time wait in the scheduled queue
+    async fn schedule_if_pending(
+        &mut self,
+        content: Content,
+        recommender: Owner,
+        reason_cid: Option<String>,
+        reason: Option<String>,
+    ) -> Result<bool, ContractError> {
+        if content.edit_of.is_some() || content.comment_to_cid.is_some() {
+            return Ok(false);
+        }
+        match content.publish_at {
+            Some(publish_at) if publish_at > system_api::current_system_time() => {
+                self.schedule_content(ScheduledContent {
+                    content,
+                    recommender,
+                    reason_cid,
+                    reason,
+                })
+                .await?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn _release_scheduled(&mut self) -> Result<Vec<String>, ContractError> {
+        let contents = self
+            .due_scheduled_contents(system_api::current_system_time())
+            .await?;
+        let cids = contents
+            .iter()
+            .map(|scheduled| scheduled.content.cid.clone())
+            .collect::<Vec<_>>();
+        self.remove_scheduled(cids.clone()).await?;
+        for scheduled in contents {
+            let content = scheduled.content;
+            self.publish_content(
+                content.cid.clone(),
+                content.title,
+                content.content,
+                content.tags,
+                content.author,
+                content.authors,
+            )
+            .await?;
+            if let Some(reason_cid) = scheduled.reason_cid {
+                self.recommend_content(
+                    content.cid,
+                    reason_cid,
+                    scheduled.reason.unwrap_or_default(),
+                    scheduled.recommender,
+                )
+                .await?;
+            }
+        }
+        Ok(cids)
+    }
+
     async fn _reject_content(
         &mut self,
         reviewer: Owner,
@@ -874,7 +1271,9 @@ impl Review {
             return Ok(());
         }
         match content {
-            Some(_content) => {
+            Some(content) => {
+                self.record_reviews(content.cid, false, content.reviewers)
+                    .await?;
                 // TODO: notify author content is rejected
             }
             _ => {
@@ -902,6 +1301,8 @@ impl Review {
         }
         match asset {
             Some(asset) => {
+                self.record_reviews(asset.cid.clone(), true, asset.reviewers.clone())
+                    .await?;
                 self.create_collection(
                     asset.base_uri,
                     asset.uris,
@@ -936,8 +1337,10 @@ impl Review {
             return Ok(());
         }
         match asset {
-            Some(_asset) => {
-                // TODO: notify author is approved
+            Some(asset) => {
+                self.record_reviews(asset.cid, false, asset.reviewers)
+                    .await?;
+                // TODO: notify author is rejected
             }
             _ => {
                 // TODO: notify author
@@ -1001,6 +1404,8 @@ impl Review {
         self.reward_credits(owner, Amount::from_tokens(50)).await?;
         self.reward_tokens().await?;
         if let Some(activity) = activity {
+            self.record_reviews(activity_id.to_string(), true, activity.reviewers)
+                .await?;
             self.lock_activity_funds(activity_id, activity.budget_amount)
                 .await?;
         }
@@ -1014,12 +1419,17 @@ impl Review {
         reason: String,
         creation_chain: bool,
     ) -> Result<(), ContractError> {
-        let _activity = self.reject_activity(owner, activity_id, reason).await?;
+        let activity = self.reject_activity(owner, activity_id, reason).await?;
         if !creation_chain {
             return Ok(());
         }
         self.reward_credits(owner, Amount::from_tokens(50)).await?;
         self.reward_tokens().await?;
+        if let Some(activity) = activity {
+            self.record_reviews(activity_id.to_string(), false, activity.reviewers)
+                .await?;
+            self.refund_activity_funds(activity_id).await?;
+        }
         Ok(())
     }
 }
@@ -1042,6 +1452,12 @@ pub enum ContractError {
     #[error("Invalid user")]
     InvalidUser,
 
+    #[error("Invalid caller")]
+    InvalidCaller,
+
+    #[error("Invalid content")]
+    InvalidContent,
+
     #[error("Cross-application sessions not supported")]
     SessionsNotSupported,
 
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use async_graphql::{Request, Response, SimpleObject};
+use feed::AuthorShare;
 use linera_sdk::base::{Amount, ApplicationId, ChainId, ContractAbi, Owner, ServiceAbi, Timestamp};
 use serde::{Deserialize, Serialize};
 
@@ -41,6 +42,11 @@ pub struct InitialState {
     pub reviewer_rejected_threshold: u16,
     pub activity_approved_threshold: u16,
     pub activity_rejected_threshold: u16,
+    pub content_takedown_threshold: u16,
+    /// Check multihash of inline content against its cid, which must then use raw codec
+    pub verify_content_cid: bool,
+    /// Max bytes of content body, 0 means no limit
+    pub max_body_bytes: u64,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
@@ -67,13 +73,44 @@ pub struct Content {
     /// Here cid is the content cid::Cid store in ipfs
     pub cid: String,
     pub comment_to_cid: Option<String>,
+    /// Cid of the published content which this one is a new revision of
+    pub edit_of: Option<String>,
     pub author: Owner,
+    /// Co-authors with the author sharing rewards and tips, empty if author takes all
+    pub authors: Vec<AuthorShare>,
     pub title: String,
     pub content: String,
+    pub tags: Vec<String>,
     pub reviewers: HashMap<Owner, Review>,
     pub approved: u16,
     pub rejected: u16,
     pub created_at: Timestamp,
+    /// Approved content waits in the scheduled queue until this time
+    pub publish_at: Option<Timestamp>,
+}
+
+/// Approved content waiting for its publish time with the recommendation of approval
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct ScheduledContent {
+    pub content: Content,
+    pub recommender: Owner,
+    pub reason_cid: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// Reviewer votes to take down published content
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Takedown {
+    pub cid: String,
+    pub reviewers: HashMap<Owner, Review>,
+    pub approved: u16,
+    pub rejected: u16,
+    pub taken_down: bool,
+    /// Reviewers kept the content, reports of it are dismissed in feed
+    pub dismissed: bool,
+    /// Reasons of the reports which flagged the content in feed
+    pub reports: Vec<String>,
+    pub created_at: Timestamp,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
@@ -121,6 +158,9 @@ pub enum Operation {
         cid: String,
         title: String,
         content: String,
+        tags: Vec<String>,
+        authors: Vec<AuthorShare>,
+        publish_at: Option<Timestamp>,
     },
     ApproveContent {
         content_cid: String,
@@ -131,11 +171,25 @@ pub enum Operation {
         content_cid: String,
         reason: Option<String>,
     },
+    TakedownContent {
+        cid: String,
+        reason: String,
+    },
+    RejectTakedown {
+        cid: String,
+        reason: String,
+    },
     SubmitComment {
         cid: String,
         comment_cid: String,
         comment: String,
     },
+    SubmitEdit {
+        cid: String,
+        new_cid: String,
+        title: String,
+        content: String,
+    },
     ApproveAsset {
         cid: String,
         reason: Option<String>,
@@ -151,6 +205,7 @@ pub enum Operation {
         price: Option<Amount>,
         name: String,
     },
+    ReleaseScheduled,
     RequestSubscribe,
     ApproveActivity {
         activity_id: u64,
@@ -186,6 +241,9 @@ pub enum Message {
         cid: String,
         title: String,
         content: String,
+        tags: Vec<String>,
+        authors: Vec<AuthorShare>,
+        publish_at: Option<Timestamp>,
     },
     ApproveContent {
         content_cid: String,
@@ -196,11 +254,25 @@ pub enum Message {
         content_cid: String,
         reason: Option<String>,
     },
+    TakedownContent {
+        cid: String,
+        reason: String,
+    },
+    RejectTakedown {
+        cid: String,
+        reason: String,
+    },
     SubmitComment {
         cid: String,
         comment_cid: String,
         comment: String,
     },
+    SubmitEdit {
+        cid: String,
+        new_cid: String,
+        title: String,
+        content: String,
+    },
     ApproveAsset {
         cid: String,
         reason: Option<String>,
@@ -216,6 +288,14 @@ pub enum Message {
         price: Option<Amount>,
         name: String,
     },
+    ReleaseScheduled,
+    ScheduledReleased {
+        cids: Vec<String>,
+    },
+    OpenTakedown {
+        cid: String,
+        reasons: Vec<String>,
+    },
     RequestSubscribe,
     InitialState {
         state: InitialState,
@@ -237,10 +317,18 @@ pub enum Message {
 
 #[derive(Debug, Deserialize, Serialize)]
 pub enum ApplicationCall {
+    /// Same as feed::ReviewCall::OpenTakedown, keep it the first variant
+    OpenTakedown {
+        cid: String,
+        reasons: Vec<String>,
+    },
     SubmitContent {
         cid: String,
         title: String,
         content: String,
+        tags: Vec<String>,
+        authors: Vec<AuthorShare>,
+        publish_at: Option<Timestamp>,
     },
     SubmitActivity {
         activity_id: u64,
@@ -251,3 +339,37 @@ pub enum ApplicationCall {
         activity_id: u64,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ApplicationCall;
+
+    #[test]
+    fn feed_review_call_decodes_as_application_call() {
+        let cid = "bafkreigh2akiscaildcqabsyg3dfr6chu3fgpregiymsck7e7aqa4s52zy".to_string();
+        let reasons = vec!["spam".to_string(), "abuse".to_string()];
+        let bytes = bcs::to_bytes(&feed::ReviewCall::OpenTakedown {
+            cid: cid.clone(),
+            reasons: reasons.clone(),
+        })
+        .unwrap();
+        assert_eq!(
+            bytes,
+            bcs::to_bytes(&ApplicationCall::OpenTakedown {
+                cid: cid.clone(),
+                reasons: reasons.clone(),
+            })
+            .unwrap()
+        );
+        match bcs::from_bytes::<ApplicationCall>(&bytes).unwrap() {
+            ApplicationCall::OpenTakedown {
+                cid: decoded_cid,
+                reasons: decoded_reasons,
+            } => {
+                assert_eq!(decoded_cid, cid);
+                assert_eq!(decoded_reasons, reasons);
+            }
+            call => panic!("Decoded as {:?}", call),
+        }
+    }
+}
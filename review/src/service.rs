@@ -5,8 +5,9 @@ mod state;
 use self::state::Review;
 use async_graphql::{EmptySubscription, Object, Request, Response, Schema};
 use async_trait::async_trait;
+use feed::AuthorShare;
 use linera_sdk::{
-    base::{Amount, Owner, WithServiceAbi},
+    base::{Amount, Owner, Timestamp, WithServiceAbi},
     QueryContext, Service, ViewStateStorage,
 };
 use review::Operation;
@@ -55,11 +56,22 @@ impl MutationRoot {
         bcs::to_bytes(&Operation::RejectReviewer { candidate, reason }).unwrap()
     }
 
-    async fn submit_content(&self, cid: String, title: String, content: String) -> Vec<u8> {
+    async fn submit_content(
+        &self,
+        cid: String,
+        title: String,
+        content: String,
+        tags: Option<Vec<String>>,
+        authors: Option<Vec<AuthorShare>>,
+        publish_at: Option<Timestamp>,
+    ) -> Vec<u8> {
         bcs::to_bytes(&Operation::SubmitContent {
             cid,
             title,
             content,
+            tags: tags.unwrap_or_default(),
+            authors: authors.unwrap_or_default(),
+            publish_at,
         })
         .unwrap()
     }
@@ -86,6 +98,14 @@ impl MutationRoot {
         .unwrap()
     }
 
+    async fn takedown_content(&self, cid: String, reason: String) -> Vec<u8> {
+        bcs::to_bytes(&Operation::TakedownContent { cid, reason }).unwrap()
+    }
+
+    async fn reject_takedown(&self, cid: String, reason: String) -> Vec<u8> {
+        bcs::to_bytes(&Operation::RejectTakedown { cid, reason }).unwrap()
+    }
+
     async fn submit_comment(&self, cid: String, comment_cid: String, comment: String) -> Vec<u8> {
         bcs::to_bytes(&Operation::SubmitComment {
             cid,
@@ -95,6 +115,22 @@ impl MutationRoot {
         .unwrap()
     }
 
+    async fn submit_edit(
+        &self,
+        cid: String,
+        new_cid: String,
+        title: String,
+        content: String,
+    ) -> Vec<u8> {
+        bcs::to_bytes(&Operation::SubmitEdit {
+            cid,
+            new_cid,
+            title,
+            content,
+        })
+        .unwrap()
+    }
+
     async fn approve_asset(&self, cid: String, reason: Option<String>) -> Vec<u8> {
         bcs::to_bytes(&Operation::ApproveAsset { cid, reason }).unwrap()
     }
@@ -121,6 +157,10 @@ impl MutationRoot {
         .unwrap()
     }
 
+    async fn release_scheduled(&self) -> Vec<u8> {
+        bcs::to_bytes(&Operation::ReleaseScheduled).unwrap()
+    }
+
     async fn request_subscribe(&self) -> Vec<u8> {
         bcs::to_bytes(&Operation::RequestSubscribe {}).unwrap()
     }
@@ -1,12 +1,14 @@
 use std::collections::HashMap;
 
 use linera_sdk::{
-    base::{Amount, ArithmeticError, ChainId, Owner},
+    base::{Amount, ArithmeticError, ChainId, Owner, Timestamp},
     contract::system_api,
     views::{MapView, RegisterView, ViewStorageContext},
 };
 use linera_views::views::{GraphQLView, RootView};
-use review::{Activity, Asset, Content, InitialState, Review as _Review, Reviewer};
+use review::{
+    Activity, Asset, Content, InitialState, Review as _Review, Reviewer, ScheduledContent, Takedown,
+};
 use thiserror::Error;
 
 #[derive(RootView, GraphQLView)]
@@ -26,6 +28,11 @@ pub struct Review {
     pub activity_applications: MapView<u64, Activity>,
     pub activity_approved_threshold: RegisterView<u16>,
     pub activity_rejected_threshold: RegisterView<u16>,
+    pub content_takedown_threshold: RegisterView<u16>,
+    pub content_takedowns: MapView<String, Takedown>,
+    pub verify_content_cid: RegisterView<bool>,
+    pub max_body_bytes: RegisterView<u64>,
+    pub scheduled_contents: MapView<String, ScheduledContent>,
 }
 
 #[allow(dead_code)]
@@ -50,6 +57,10 @@ impl Review {
             .set(state.activity_approved_threshold);
         self.activity_rejected_threshold
             .set(state.activity_rejected_threshold);
+        self.content_takedown_threshold
+            .set(state.content_takedown_threshold);
+        self.verify_content_cid.set(state.verify_content_cid);
+        self.max_body_bytes.set(state.max_body_bytes);
         Ok(())
     }
 
@@ -63,6 +74,9 @@ impl Review {
             reviewer_rejected_threshold: *self.reviewer_rejected_threshold.get(),
             activity_approved_threshold: *self.activity_approved_threshold.get(),
             activity_rejected_threshold: *self.activity_rejected_threshold.get(),
+            content_takedown_threshold: *self.content_takedown_threshold.get(),
+            verify_content_cid: *self.verify_content_cid.get(),
+            max_body_bytes: *self.max_body_bytes.get(),
         })
     }
 
@@ -272,6 +286,18 @@ impl Review {
     }
 
     pub(crate) async fn submit_content(&mut self, content: Content) -> Result<(), StateError> {
+        if !feed::validate_shares(content.author, &content.authors) {
+            return Err(StateError::InvalidShares);
+        }
+        let size = content.content.len() as u64;
+        let max_size = *self.max_body_bytes.get();
+        if max_size > 0 && size > max_size {
+            return Err(StateError::BodyTooLarge(size, max_size));
+        }
+        match *self.verify_content_cid.get() {
+            true => feed::verify_content_cid(&content.cid, &content.content)?,
+            false => feed::parse_cid(&content.cid)?,
+        };
         self.content_applications
             .insert(&content.clone().cid, content)?;
         Ok(())
@@ -317,6 +343,122 @@ impl Review {
         Ok(None)
     }
 
+    pub(crate) async fn schedule_content(
+        &mut self,
+        scheduled: ScheduledContent,
+    ) -> Result<(), StateError> {
+        self.scheduled_contents
+            .insert(&scheduled.content.cid.clone(), scheduled)?;
+        Ok(())
+    }
+
+    pub(crate) async fn due_scheduled_contents(
+        &self,
+        now: Timestamp,
+    ) -> Result<Vec<ScheduledContent>, StateError> {
+        let mut contents = Vec::new();
+        for cid in self.scheduled_contents.indices().await? {
+            if let Some(scheduled) = self.scheduled_contents.get(&cid).await? {
+                if scheduled
+                    .content
+                    .publish_at
+                    .map_or(true, |publish_at| publish_at <= now)
+                {
+                    contents.push(scheduled);
+                }
+            }
+        }
+        contents.sort_by_key(|scheduled| scheduled.content.publish_at);
+        Ok(contents)
+    }
+
+    pub(crate) async fn remove_scheduled(&mut self, cids: Vec<String>) -> Result<(), StateError> {
+        for cid in cids {
+            self.scheduled_contents.remove(&cid)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn open_takedown(
+        &mut self,
+        cid: String,
+        reasons: Vec<String>,
+    ) -> Result<(), StateError> {
+        let mut takedown = match self.content_takedowns.get(&cid).await? {
+            Some(takedown) => takedown,
+            _ => Takedown {
+                cid: cid.clone(),
+                reviewers: HashMap::default(),
+                approved: 0,
+                rejected: 0,
+                taken_down: false,
+                dismissed: false,
+                reports: Vec::new(),
+                created_at: system_api::current_system_time(),
+            },
+        };
+        if takedown.taken_down || takedown.dismissed {
+            return Ok(());
+        }
+        takedown.reports = reasons;
+        self.content_takedowns.insert(&cid, takedown)?;
+        Ok(())
+    }
+
+    // Returns the takedown when votes just reach the threshold, either to take down or to keep the content
+    pub(crate) async fn vote_takedown(
+        &mut self,
+        reviewer: Owner,
+        cid: String,
+        reason: String,
+        approved: bool,
+    ) -> Result<Option<Takedown>, StateError> {
+        if !self.is_reviewer(reviewer).await? {
+            return Err(StateError::InvalidReviewer);
+        }
+        let mut takedown = match self.content_takedowns.get(&cid).await? {
+            Some(takedown) => takedown,
+            _ => Takedown {
+                cid: cid.clone(),
+                reviewers: HashMap::default(),
+                approved: 0,
+                rejected: 0,
+                taken_down: false,
+                dismissed: false,
+                reports: Vec::new(),
+                created_at: system_api::current_system_time(),
+            },
+        };
+        if takedown.taken_down || takedown.dismissed || takedown.reviewers.contains_key(&reviewer) {
+            return Err(StateError::AlreadyReviewed);
+        }
+        match approved {
+            true => takedown.approved += 1,
+            false => takedown.rejected += 1,
+        }
+        takedown.reviewers.insert(
+            reviewer,
+            _Review {
+                reviewer,
+                approved,
+                reason,
+                created_at: system_api::current_system_time(),
+            },
+        );
+        let takedown_threshold = *self.content_takedown_threshold.get();
+        let reviewer_number = *self.reviewer_number.get();
+        if takedown.approved >= takedown_threshold || takedown.approved >= reviewer_number {
+            takedown.taken_down = true;
+        } else if takedown.rejected >= takedown_threshold || takedown.rejected >= reviewer_number {
+            takedown.dismissed = true;
+        }
+        self.content_takedowns.insert(&cid, takedown.clone())?;
+        match takedown.taken_down || takedown.dismissed {
+            true => Ok(Some(takedown)),
+            false => Ok(None),
+        }
+    }
+
     pub(crate) async fn reject_content(
         &mut self,
         reviewer: Owner,
@@ -544,7 +686,7 @@ impl Review {
             owner.clone(),
             _Review {
                 reviewer: owner,
-                approved: true,
+                approved: false,
                 reason,
                 created_at: system_api::current_system_time(),
             },
@@ -600,4 +742,13 @@ pub enum StateError {
 
     #[error("Invalid activity")]
     InvalidActivity,
+
+    #[error("Invalid author shares")]
+    InvalidShares,
+
+    #[error("Invalid content cid: {0}")]
+    InvalidCid(#[from] feed::CidError),
+
+    #[error("Body of {0} bytes exceeds limit of {1} bytes")]
+    BodyTooLarge(u64, u64),
 }